@@ -1,4 +1,6 @@
+use crate::config::BackendSettings;
 use crate::parser::EncoderType;
+use crate::{escape_gleam_string, GlossError, Result};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -23,6 +25,25 @@ pub trait EncoderBackend: Send + Sync {
     /// Format an empty object literal at the provided indentation level
     fn encode_empty_object(&self, indent: &str) -> String;
 
+    /// Format a single key/value entry as used by `encode_object_from_entry_lists`
+    fn encode_entry(&self, key: &str, value_expr: &str) -> String {
+        format!(r#"#("{}", {})"#, key, value_expr)
+    }
+
+    /// Format an object whose entries are only known at runtime. Each of `entry_lists` is a
+    /// Gleam expression evaluating to a list of entries, and `flatten` is the qualified
+    /// function concatenating them (e.g. `list.flatten`).
+    /// Returns `None` when the backend cannot leave out entries at runtime.
+    fn encode_object_from_entry_lists(
+        &self,
+        _indent: &str,
+        _entry_lists: &[String],
+        _closing_indent: &str,
+        _flatten: &str,
+    ) -> Option<String> {
+        None
+    }
+
     /// Literal encoding helpers
     fn encode_string_literal(&self, value: &str) -> String;
 
@@ -36,21 +57,105 @@ pub trait EncoderBackend: Send + Sync {
     fn encode_nullable(&self, value_expr: &str, inner_encoder: &str) -> String;
     fn encode_array(&self, value_expr: &str, inner_encoder: &str) -> String;
 
+    /// Encode a `Dict(String, v)` as an object, using `inner_encoder` for the values.
+    /// Returns `None` when the backend has no representation for dictionaries.
+    fn encode_dict(&self, _value_expr: &str, _inner_encoder: &str) -> Option<String> {
+        None
+    }
+
+    /// Encode a fixed-length, heterogeneous array from already encoded elements (used for tuples).
+    /// Returns `None` when the backend has no representation for tuples.
+    fn encode_tuple(&self, _elements: &[String]) -> Option<String> {
+        None
+    }
+
+    /// Encode `Nil`. Returns `None` when the backend has no representation for it.
+    fn encode_nil(&self) -> Option<String> {
+        None
+    }
+
+    /// Encode a `BitArray`. `text_expr` is the same value already written as base64 or hex text,
+    /// following `bit_array_encoding`, for backends without a binary representation; it is
+    /// empty when `bit_array_text_form` returns `false`.
+    /// Returns `None` when the backend cannot encode bit arrays.
+    fn encode_bit_array(&self, _value_expr: &str, _text_expr: &str) -> Option<String> {
+        None
+    }
+
+    /// Whether `encode_bit_array` writes the text form. Backends with a binary representation
+    /// return `false`, so that `gleam/bit_array` is not imported for nothing.
+    fn bit_array_text_form(&self) -> bool {
+        true
+    }
+
+    /// Turn the encoder expression `body` for `param` into a function value, as passed to
+    /// `encode_nullable`, `encode_array`, `encode_dict` and generic encoders.
+    /// Plain calls such as `json.string(value)` collapse to the function reference itself.
+    fn encoder_function(&self, param: &str, body: &str) -> String {
+        if let Some(callee) = body.strip_suffix(&format!("({})", param)) {
+            let is_reference = !callee.is_empty()
+                && callee
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '.');
+            if is_reference {
+                return callee.to_string();
+            }
+        }
+
+        format!("fn({}) {{ {} }}", param, body)
+    }
+
+    /// Module path and import alias of the `gleam/json` compatible module whose `Json` values
+    /// the encoders return. Only such backends can encode `Dynamic` values, and only those
+    /// using `gleam/json` itself can write existing `json.Json` values.
+    fn json_module(&self) -> Option<(&str, &str)> {
+        None
+    }
+
     /// Gleam package dependencies that must exist in gleam.toml
     fn required_packages(&self) -> &[&'static str] {
         &[]
     }
+
+    /// Copy of this backend adjusted by its `[backend.<name>]` table in `gloss.toml`, or
+    /// `None` to keep using it unchanged. Backends without settings reject every key.
+    fn configure(&self, settings: &BackendSettings) -> Result<Option<EncoderBackendRef>> {
+        match settings.keys().next() {
+            None => Ok(None),
+            Some(key) => Err(GlossError::GenerationError(format!(
+                "Encoder backend `{}` has no setting `{}`",
+                self.name(),
+                key
+            ))),
+        }
+    }
 }
 
-#[derive(Default)]
-pub struct JsonEncoderBackend;
+/// Encoder targeting `gleam/json`. The module, its import alias and the return type can be
+/// changed through `[backend.json]`, e.g. to use a fork of the library.
+#[derive(Debug, Clone)]
+pub struct JsonEncoderBackend {
+    module: String,
+    alias: String,
+    return_type: String,
+}
+
+impl Default for JsonEncoderBackend {
+    fn default() -> Self {
+        Self {
+            module: Self::MODULE.to_string(),
+            alias: "json".to_string(),
+            return_type: "Json".to_string(),
+        }
+    }
+}
 
 impl JsonEncoderBackend {
-    const ALIAS: &'static str = "json";
-    const RETURN_TYPE: &'static str = "Json";
+    const MODULE: &'static str = "gleam/json";
+    const SETTINGS: [&'static str; 3] = ["alias", "module", "return_type"];
 
-    fn qualify(fn_name: &str) -> String {
-        format!("{}.{}", Self::ALIAS, fn_name)
+    fn qualify(&self, fn_name: &str) -> String {
+        format!("{}.{}", self.alias, fn_name)
     }
 }
 
@@ -60,11 +165,15 @@ impl EncoderBackend for JsonEncoderBackend {
     }
 
     fn module_imports(&self) -> Vec<String> {
-        vec!["import gleam/json".to_string()]
+        if self.module.rsplit('/').next() == Some(self.alias.as_str()) {
+            vec![format!("import {}", self.module)]
+        } else {
+            vec![format!("import {} as {}", self.module, self.alias)]
+        }
     }
 
     fn return_type(&self) -> String {
-        format!("{}.{}", Self::ALIAS, Self::RETURN_TYPE)
+        self.qualify(&self.return_type)
     }
 
     fn encode_object(
@@ -86,44 +195,67 @@ impl EncoderBackend for JsonEncoderBackend {
         format!(
             "{}{}([\n{}\n{}])",
             closing_indent,
-            Self::qualify("object"),
+            self.qualify("object"),
             entries,
             closing_indent
         )
     }
 
     fn encode_empty_object(&self, indent: &str) -> String {
-        format!("{}{}([])", indent, Self::qualify("object"))
+        format!("{}{}([])", indent, self.qualify("object"))
+    }
+
+    fn encode_object_from_entry_lists(
+        &self,
+        indent: &str,
+        entry_lists: &[String],
+        closing_indent: &str,
+        flatten: &str,
+    ) -> Option<String> {
+        let lists = entry_lists
+            .iter()
+            .map(|list| format!("{indent}  {list}"))
+            .collect::<Vec<_>>()
+            .join(",\n");
+
+        Some(format!(
+            "{}{}({}([\n{}\n{}]))",
+            closing_indent,
+            self.qualify("object"),
+            flatten,
+            lists,
+            closing_indent
+        ))
     }
 
     fn encode_string_literal(&self, value: &str) -> String {
         format!(
             r#"{alias}.string("{value}")"#,
-            alias = Self::ALIAS,
-            value = value
+            alias = self.alias,
+            value = escape_gleam_string(value)
         )
     }
 
     fn encode_string(&self, value_expr: &str) -> String {
-        format!("{}({})", Self::qualify("string"), value_expr)
+        format!("{}({})", self.qualify("string"), value_expr)
     }
 
     fn encode_int(&self, value_expr: &str) -> String {
-        format!("{}({})", Self::qualify("int"), value_expr)
+        format!("{}({})", self.qualify("int"), value_expr)
     }
 
     fn encode_float(&self, value_expr: &str) -> String {
-        format!("{}({})", Self::qualify("float"), value_expr)
+        format!("{}({})", self.qualify("float"), value_expr)
     }
 
     fn encode_bool(&self, value_expr: &str) -> String {
-        format!("{}({})", Self::qualify("bool"), value_expr)
+        format!("{}({})", self.qualify("bool"), value_expr)
     }
 
     fn encode_nullable(&self, value_expr: &str, inner_encoder: &str) -> String {
         format!(
             "{}({}, {})",
-            Self::qualify("nullable"),
+            self.qualify("nullable"),
             value_expr,
             inner_encoder
         )
@@ -132,44 +264,433 @@ impl EncoderBackend for JsonEncoderBackend {
     fn encode_array(&self, value_expr: &str, inner_encoder: &str) -> String {
         format!(
             "{}({}, {})",
-            Self::qualify("array"),
+            self.qualify("array"),
             value_expr,
             inner_encoder
         )
     }
 
+    fn encode_dict(&self, value_expr: &str, inner_encoder: &str) -> Option<String> {
+        Some(format!(
+            "{}({}, fn(key) {{ key }}, {})",
+            self.qualify("dict"),
+            value_expr,
+            inner_encoder
+        ))
+    }
+
+    fn encode_tuple(&self, elements: &[String]) -> Option<String> {
+        Some(format!(
+            "{}([{}])",
+            self.qualify("preprocessed_array"),
+            elements.join(", ")
+        ))
+    }
+
+    fn encode_nil(&self) -> Option<String> {
+        Some(format!("{}()", self.qualify("null")))
+    }
+
+    fn encode_bit_array(&self, _value_expr: &str, text_expr: &str) -> Option<String> {
+        Some(self.encode_string(text_expr))
+    }
+
+    fn json_module(&self) -> Option<(&str, &str)> {
+        Some((self.module.as_str(), self.alias.as_str()))
+    }
+
     fn required_packages(&self) -> &[&'static str] {
-        &["gleam/json"]
+        // A replacement module comes from a package gloss does not know about
+        if self.module == Self::MODULE {
+            &[Self::MODULE]
+        } else {
+            &[]
+        }
+    }
+
+    fn configure(&self, settings: &BackendSettings) -> Result<Option<EncoderBackendRef>> {
+        let mut configured = self.clone();
+        let mut alias = None;
+        for (key, value) in settings {
+            let value = value.as_str().ok_or_else(|| {
+                GlossError::GenerationError(format!(
+                    "Setting `{}` of the json backend must be a string",
+                    key
+                ))
+            })?;
+            match key.as_str() {
+                "module" => configured.module = value.to_string(),
+                "alias" => alias = Some(value.to_string()),
+                "return_type" => configured.return_type = value.to_string(),
+                _ => {
+                    return Err(GlossError::GenerationError(format!(
+                        "Unknown setting `{}` for the json backend. Expected one of: {}",
+                        key,
+                        Self::SETTINGS.join(", ")
+                    )))
+                }
+            }
+        }
+        // Without an explicit alias the module is referred to by its last segment
+        configured.alias = alias.unwrap_or_else(|| {
+            configured
+                .module
+                .rsplit('/')
+                .next()
+                .unwrap_or(&configured.module)
+                .to_string()
+        });
+        Ok(Some(Arc::new(configured)))
+    }
+}
+
+/// Target of generated decoders. Methods returning a step produce a `use` line binding
+/// `binding` for the rest of the decoder; the others produce decoder expressions.
+pub trait DecoderBackend: Send + Sync {
+    /// Human-readable backend identifier (used for debugging and equality checks)
+    fn name(&self) -> &str;
+
+    /// Import statements required for decoder generation
+    fn module_imports(&self) -> Vec<String>;
+
+    /// Type of a decoder producing `inner` (e.g. "decode.Decoder(User)")
+    fn decoder_type(&self, inner: &str) -> String;
+
+    /// Read the value under `key`, failing when it is missing
+    fn field(&self, binding: &str, key: &str, decoder: &str) -> String;
+
+    /// Read the value under `key`, using `default_expr` when it is missing
+    fn optional_field(&self, binding: &str, key: &str, default_expr: &str, decoder: &str)
+        -> String;
+
+    /// Read the value under a path of keys, failing when any of them is missing
+    fn subfield(&self, binding: &str, path: &[String], decoder: &str) -> String;
+
+    /// Run `decoder` on the current value
+    fn then(&self, binding: &str, decoder: &str) -> String;
+
+    /// Decoder reading the value under a path of keys
+    fn at(&self, path: &[String], decoder: &str) -> String;
+
+    /// Decoder reading the value under a path of keys, or `default_expr` when it is missing
+    fn optionally_at(&self, path: &[String], default_expr: &str, decoder: &str) -> String;
+
+    /// Decoder reading the element at `index` of a sequence (used for tuples)
+    fn element(&self, index: usize, decoder: &str) -> String;
+
+    /// Decoders ending a decoder successfully or with a failure. `message_expr` is a Gleam
+    /// expression evaluating to the expectation reported in the error.
+    fn success(&self, value_expr: &str) -> String;
+    fn failure(&self, default_expr: &str, message_expr: &str) -> String;
+
+    /// Combinators over decoders; `next_fn` and `map_fn` are Gleam function expressions
+    fn and_then(&self, decoder: &str, next_fn: &str) -> String;
+    fn map(&self, decoder: &str, map_fn: &str) -> String;
+    fn one_of(&self, first: &str, alternatives: &[String]) -> String;
+    fn recursive(&self, decoder: &str) -> String;
+
+    /// Decode primitive values
+    fn string(&self) -> String;
+    fn int(&self) -> String;
+    fn float(&self) -> String;
+    fn bool(&self) -> String;
+
+    /// Decode collections
+    fn optional(&self, inner_decoder: &str) -> String;
+    fn list(&self, inner_decoder: &str) -> String;
+
+    /// Decode an object as a `Dict(String, v)`, using `inner_decoder` for the values.
+    /// Returns `None` when the backend cannot read arbitrary keys.
+    fn string_dict(&self, _inner_decoder: &str) -> Option<String> {
+        None
+    }
+
+    /// Decode any value as `Dynamic`.
+    /// Returns `None` when the backend has no representation for untyped values.
+    fn dynamic(&self) -> Option<String> {
+        None
+    }
+
+    /// Gleam package dependencies that must exist in gleam.toml
+    fn required_packages(&self) -> &[&'static str] {
+        &[]
+    }
+}
+
+/// Decoders built with `gleam/dynamic/decode`, the default for `decoder` annotations
+#[derive(Default)]
+pub struct DynamicDecoderBackend;
+
+impl DynamicDecoderBackend {
+    pub const NAME: &'static str = "dynamic";
+    const ALIAS: &'static str = "decode";
+
+    fn qualify(fn_name: &str) -> String {
+        format!("{}.{}", Self::ALIAS, fn_name)
+    }
+
+    fn keys(path: &[String]) -> String {
+        path.iter()
+            .map(|key| string_literal(key))
+            .collect::<Vec<_>>()
+            .join(", ")
     }
 }
 
+impl DecoderBackend for DynamicDecoderBackend {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn module_imports(&self) -> Vec<String> {
+        vec!["import gleam/dynamic/decode".to_string()]
+    }
+
+    fn decoder_type(&self, inner: &str) -> String {
+        format!("{}({})", Self::qualify("Decoder"), inner)
+    }
+
+    fn field(&self, binding: &str, key: &str, decoder: &str) -> String {
+        format!(
+            "use {} <- {}({}, {})",
+            binding,
+            Self::qualify("field"),
+            string_literal(key),
+            decoder
+        )
+    }
+
+    fn optional_field(
+        &self,
+        binding: &str,
+        key: &str,
+        default_expr: &str,
+        decoder: &str,
+    ) -> String {
+        format!(
+            "use {} <- {}({}, {}, {})",
+            binding,
+            Self::qualify("optional_field"),
+            string_literal(key),
+            default_expr,
+            decoder
+        )
+    }
+
+    fn subfield(&self, binding: &str, path: &[String], decoder: &str) -> String {
+        format!(
+            "use {} <- {}([{}], {})",
+            binding,
+            Self::qualify("subfield"),
+            Self::keys(path),
+            decoder
+        )
+    }
+
+    fn then(&self, binding: &str, decoder: &str) -> String {
+        format!("use {} <- {}({})", binding, Self::qualify("then"), decoder)
+    }
+
+    fn at(&self, path: &[String], decoder: &str) -> String {
+        format!(
+            "{}([{}], {})",
+            Self::qualify("at"),
+            Self::keys(path),
+            decoder
+        )
+    }
+
+    fn optionally_at(&self, path: &[String], default_expr: &str, decoder: &str) -> String {
+        format!(
+            "{}([{}], {}, {})",
+            Self::qualify("optionally_at"),
+            Self::keys(path),
+            default_expr,
+            decoder
+        )
+    }
+
+    fn element(&self, index: usize, decoder: &str) -> String {
+        format!("{}([{}], {})", Self::qualify("at"), index, decoder)
+    }
+
+    fn success(&self, value_expr: &str) -> String {
+        format!("{}({})", Self::qualify("success"), value_expr)
+    }
+
+    fn failure(&self, default_expr: &str, message_expr: &str) -> String {
+        format!(
+            "{}({}, {})",
+            Self::qualify("failure"),
+            default_expr,
+            message_expr
+        )
+    }
+
+    fn and_then(&self, decoder: &str, next_fn: &str) -> String {
+        format!("{}({}, {})", Self::qualify("then"), decoder, next_fn)
+    }
+
+    fn map(&self, decoder: &str, map_fn: &str) -> String {
+        format!("{}({}, {})", Self::qualify("map"), decoder, map_fn)
+    }
+
+    fn one_of(&self, first: &str, alternatives: &[String]) -> String {
+        // One alternative per line, as they are usually whole variant decoders
+        let rest = if alternatives.is_empty() {
+            "[]".to_string()
+        } else {
+            let lines = alternatives
+                .iter()
+                .map(|alternative| format!("    {}", alternative))
+                .collect::<Vec<_>>();
+            format!("[\n{}\n  ]", lines.join(",\n"))
+        };
+        format!("{}({}, {})", Self::qualify("one_of"), first, rest)
+    }
+
+    fn recursive(&self, decoder: &str) -> String {
+        format!("{}(fn() {{ {} }})", Self::qualify("recursive"), decoder)
+    }
+
+    fn string(&self) -> String {
+        Self::qualify("string")
+    }
+
+    fn int(&self) -> String {
+        Self::qualify("int")
+    }
+
+    fn float(&self) -> String {
+        Self::qualify("float")
+    }
+
+    fn bool(&self) -> String {
+        Self::qualify("bool")
+    }
+
+    fn optional(&self, inner_decoder: &str) -> String {
+        format!("{}({})", Self::qualify("optional"), inner_decoder)
+    }
+
+    fn list(&self, inner_decoder: &str) -> String {
+        format!("{}({})", Self::qualify("list"), inner_decoder)
+    }
+
+    fn string_dict(&self, inner_decoder: &str) -> Option<String> {
+        Some(format!(
+            "{}({}, {})",
+            Self::qualify("dict"),
+            Self::qualify("string"),
+            inner_decoder
+        ))
+    }
+
+    fn dynamic(&self) -> Option<String> {
+        Some(Self::qualify("dynamic"))
+    }
+}
+
+/// Quoted Gleam string literal
+fn string_literal(value: &str) -> String {
+    format!("\"{}\"", escape_gleam_string(value))
+}
+
+pub type DecoderBackendRef = Arc<dyn DecoderBackend + Send + Sync>;
+
 pub type EncoderBackendRef = Arc<dyn EncoderBackend + Send + Sync>;
 
+/// Encoder and decoder backends addressable from `encoder(<name>)` and `decoder(<name>)`
+/// annotations, keyed by name
 #[derive(Default, Clone)]
 pub struct BackendRegistry {
-    backends: HashMap<EncoderType, EncoderBackendRef>,
+    backends: HashMap<String, EncoderBackendRef>,
+    decoder_backends: HashMap<String, DecoderBackendRef>,
 }
 
 impl BackendRegistry {
     pub fn new() -> Self {
         let mut registry = HashMap::new();
         registry.insert(
-            EncoderType::Json,
+            EncoderType::Json.identifier().to_string(),
             Arc::new(JsonEncoderBackend::default()) as EncoderBackendRef,
         );
-        Self { backends: registry }
+        let mut decoder_registry = HashMap::new();
+        decoder_registry.insert(
+            DynamicDecoderBackend::NAME.to_string(),
+            Arc::new(DynamicDecoderBackend) as DecoderBackendRef,
+        );
+        Self {
+            backends: registry,
+            decoder_backends: decoder_registry,
+        }
     }
 
-    pub fn with_backend(mut self, encoder_type: EncoderType, backend: EncoderBackendRef) -> Self {
-        self.backends.insert(encoder_type, backend);
+    /// Register `backend` under a name, replacing any backend already using it
+    pub fn with_backend(
+        mut self,
+        encoder_type: impl Into<EncoderType>,
+        backend: EncoderBackendRef,
+    ) -> Self {
+        let encoder_type = encoder_type.into();
+        self.backends
+            .insert(encoder_type.identifier().to_string(), backend);
         self
     }
 
-    pub fn get(&self, encoder_type: EncoderType) -> Option<&EncoderBackendRef> {
-        self.backends.get(&encoder_type)
+    pub fn get(&self, encoder_type: &EncoderType) -> Option<&EncoderBackendRef> {
+        self.backends.get(encoder_type.identifier())
+    }
+
+    /// Like `get`, but reports unknown names together with the registered ones
+    pub fn resolve(&self, encoder_type: &EncoderType) -> Result<&EncoderBackendRef> {
+        self.get(encoder_type).ok_or_else(|| {
+            GlossError::GenerationError(format!(
+                "No encoder backend registered for `encoder({})`. Registered backends: {}",
+                encoder_type.identifier(),
+                self.names().join(", ")
+            ))
+        })
+    }
+
+    /// Registered backend names in alphabetical order
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.backends.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        names
     }
 
     pub fn values(&self) -> impl Iterator<Item = &EncoderBackendRef> {
         self.backends.values()
     }
+
+    /// Register a decoder backend under a name, replacing any backend already using it
+    pub fn with_decoder_backend(mut self, name: &str, backend: DecoderBackendRef) -> Self {
+        self.decoder_backends.insert(name.to_string(), backend);
+        self
+    }
+
+    pub fn get_decoder(&self, name: &str) -> Option<&DecoderBackendRef> {
+        self.decoder_backends.get(name)
+    }
+
+    /// Decoder backend requested by `decoder(<name>)`, or the `gleam/dynamic/decode` one for a
+    /// plain `decoder` annotation
+    pub fn resolve_decoder(&self, name: Option<&str>) -> Result<DecoderBackendRef> {
+        let name = name.unwrap_or(DynamicDecoderBackend::NAME);
+        match self.get_decoder(name) {
+            Some(backend) => Ok(backend.clone()),
+            None if name == DynamicDecoderBackend::NAME => Ok(Arc::new(DynamicDecoderBackend)),
+            None => {
+                let mut names: Vec<&str> =
+                    self.decoder_backends.keys().map(String::as_str).collect();
+                names.sort_unstable();
+                Err(GlossError::GenerationError(format!(
+                    "No decoder backend registered for `decoder({})`. Registered backends: {}",
+                    name,
+                    names.join(", ")
+                )))
+            }
+        }
+    }
 }
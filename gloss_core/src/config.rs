@@ -1,5 +1,6 @@
 use camino::Utf8PathBuf;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,6 +14,14 @@ pub struct Config {
     #[serde(default)]
     pub absent_field_mode: AbsentFieldMode,
 
+    /// How encoders write `Option(T)` fields holding `None` (default: null)
+    #[serde(default)]
+    pub absent_encoding: Option<AbsentEncoding>,
+
+    /// How encoders write `BitArray` values as text (default: base64)
+    #[serde(default)]
+    pub bit_array_encoding: Option<BitArrayEncoding>,
+
     /// Default failure message when an unknown variant is encountered during decoding
     #[serde(default)]
     pub decoder_unknown_variant_message: Option<String>,
@@ -24,6 +33,52 @@ pub struct Config {
     /// Naming configuration for generated functions
     #[serde(default)]
     pub fn_naming: FnNamingConfig,
+
+    /// Naming convention for variant tags (default: snake_case)
+    #[serde(default)]
+    pub variant_naming_strategy: Option<FieldNamingConvention>,
+
+    /// How variants of custom types with fields are represented (default: internal)
+    #[serde(default)]
+    pub enum_representation: Option<EnumRepresentation>,
+
+    /// Key holding the variant tag for internally and adjacently tagged types (default: "type")
+    #[serde(default)]
+    pub type_tag_field: Option<String>,
+
+    /// Key holding the variant fields for adjacently tagged types (default: "data")
+    #[serde(default)]
+    pub content_field: Option<String>,
+
+    /// Whether generated decoders fail on keys that no field reads (default: false)
+    #[serde(default)]
+    pub deny_unknown_fields: Option<bool>,
+
+    /// External programs generating encoders for `encoder(<name>)`, keyed by backend name
+    #[serde(default)]
+    pub backends: BTreeMap<String, PluginConfig>,
+
+    /// Settings handed to encoder backends (`[backend.<name>]`), keyed by backend name
+    #[serde(default)]
+    pub backend: BTreeMap<String, BackendSettings>,
+}
+
+/// Contents of a `[backend.<name>]` table; which keys mean something is up to the backend
+pub type BackendSettings = BTreeMap<String, toml::Value>;
+
+/// Encoder backend implemented by an external program (`[backends.<name>]`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PluginConfig {
+    /// Program to run; paths containing `/` are relative to the project root
+    pub command: String,
+
+    /// Arguments passed to the program
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Modules the generated code needs imported, e.g. `"my/yaml"`
+    #[serde(default)]
+    pub imports: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -98,9 +153,18 @@ impl Default for Config {
         Self {
             field_naming_strategy: FieldNamingConvention::SnakeCase,
             absent_field_mode: AbsentFieldMode::ErrorIfAbsent,
+            absent_encoding: None,
+            bit_array_encoding: None,
             decoder_unknown_variant_message: None,
             output: OutputConfig::default(),
             fn_naming: FnNamingConfig::default(),
+            variant_naming_strategy: None,
+            enum_representation: None,
+            type_tag_field: None,
+            content_field: None,
+            deny_unknown_fields: None,
+            backends: BTreeMap::new(),
+            backend: BTreeMap::new(),
         }
     }
 }
@@ -112,6 +176,12 @@ pub enum FieldNamingConvention {
     SnakeCase,
     /// Use camelCase for JSON field names
     CamelCase,
+    /// Use kebab-case for JSON field names
+    KebabCase,
+    /// Use PascalCase for JSON field names
+    PascalCase,
+    /// Use SCREAMING_SNAKE_CASE for JSON field names
+    ScreamingSnakeCase,
 }
 
 impl Default for FieldNamingConvention {
@@ -120,6 +190,21 @@ impl Default for FieldNamingConvention {
     }
 }
 
+impl FieldNamingConvention {
+    /// Parse a convention from its `gloss.toml` name (`kebab_case`) or the way it is
+    /// written in annotations (`kebab-case`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "snake_case" => Some(Self::SnakeCase),
+            "camel_case" | "camelCase" => Some(Self::CamelCase),
+            "kebab_case" | "kebab-case" => Some(Self::KebabCase),
+            "pascal_case" | "PascalCase" => Some(Self::PascalCase),
+            "screaming_snake_case" | "SCREAMING_SNAKE_CASE" => Some(Self::ScreamingSnakeCase),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum AbsentFieldMode {
@@ -138,6 +223,79 @@ impl Default for AbsentFieldMode {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbsentEncoding {
+    /// `None` is written as `null`
+    Null,
+    /// The key is left out of the object when the value is `None`
+    Omit,
+}
+
+impl Default for AbsentEncoding {
+    fn default() -> Self {
+        Self::Null
+    }
+}
+
+impl AbsentEncoding {
+    /// Parse the name used in `gloss.toml` and annotations
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "null" => Some(Self::Null),
+            "omit" => Some(Self::Omit),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BitArrayEncoding {
+    /// Padded base64 text (`gleam/bit_array.base64_encode`)
+    Base64,
+    /// Uppercase hexadecimal text (`gleam/bit_array.base16_encode`)
+    Hex,
+}
+
+impl Default for BitArrayEncoding {
+    fn default() -> Self {
+        Self::Base64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnumRepresentation {
+    /// Tag stored next to the variant fields: `{"type": "image", "url": ...}`
+    Internal,
+    /// Tag and fields stored under separate keys: `{"type": "image", "data": {...}}`
+    Adjacent,
+    /// Variant name wraps the fields: `{"image": {...}}`
+    External,
+    /// No tag at all; decoding tries each variant in order
+    Untagged,
+}
+
+impl Default for EnumRepresentation {
+    fn default() -> Self {
+        Self::Internal
+    }
+}
+
+impl EnumRepresentation {
+    /// Parse the name used in `gloss.toml` and annotations
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "internal" => Some(Self::Internal),
+            "adjacent" => Some(Self::Adjacent),
+            "external" => Some(Self::External),
+            "untagged" => Some(Self::Untagged),
+            _ => None,
+        }
+    }
+}
+
 impl Config {
     pub fn new(
         field_naming_strategy: FieldNamingConvention,
@@ -146,9 +304,18 @@ impl Config {
         Self {
             field_naming_strategy,
             absent_field_mode,
+            absent_encoding: None,
+            bit_array_encoding: None,
             decoder_unknown_variant_message: None,
             output: OutputConfig::default(),
             fn_naming: FnNamingConfig::default(),
+            variant_naming_strategy: None,
+            enum_representation: None,
+            type_tag_field: None,
+            content_field: None,
+            deny_unknown_fields: None,
+            backends: BTreeMap::new(),
+            backend: BTreeMap::new(),
         }
     }
 
@@ -221,11 +388,33 @@ impl Config {
         Self {
             field_naming_strategy: other.field_naming_strategy, // For enums, other always wins
             absent_field_mode: other.absent_field_mode,
+            absent_encoding: other.absent_encoding.or(self.absent_encoding),
+            bit_array_encoding: other.bit_array_encoding.or(self.bit_array_encoding),
             decoder_unknown_variant_message: other
                 .decoder_unknown_variant_message
                 .or(self.decoder_unknown_variant_message),
             output: self.output.merge_with(other.output),
             fn_naming: self.fn_naming.merge_with(other.fn_naming),
+            variant_naming_strategy: other
+                .variant_naming_strategy
+                .or(self.variant_naming_strategy),
+            enum_representation: other.enum_representation.or(self.enum_representation),
+            type_tag_field: other.type_tag_field.or(self.type_tag_field),
+            content_field: other.content_field.or(self.content_field),
+            deny_unknown_fields: other.deny_unknown_fields.or(self.deny_unknown_fields),
+            backends: {
+                let mut backends = self.backends;
+                backends.extend(other.backends);
+                backends
+            },
+            backend: {
+                // Tables merge key by key, so a subdirectory can change a single setting
+                let mut backend = self.backend;
+                for (name, settings) in other.backend {
+                    backend.entry(name).or_default().extend(settings);
+                }
+                backend
+            },
         }
     }
 }
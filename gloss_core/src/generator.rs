@@ -1,10 +1,17 @@
-use crate::backend::EncoderBackend;
-use crate::config::{AbsentFieldMode, Config, FieldNamingConvention};
+use crate::backend::{DecoderBackend, DynamicDecoderBackend, EncoderBackend};
+use crate::config::{
+    AbsentEncoding, AbsentFieldMode, BitArrayEncoding, Config, EnumRepresentation,
+    FieldNamingConvention,
+};
 use crate::parser::{
-    ConstructorInfo, CustomTypeInfo, EncoderType, FieldInfo, FieldMarker, TypeExpression,
+    ConstructorInfo, CustomTypeInfo, EncoderType, FieldDefault, FieldInfo, FieldMarker,
+    TypeExpression,
 };
 use crate::Result;
-use crate::{find_type_entry, module_alias, GlossError, ImportEntry, TypeLookup, TypeRegistry};
+use crate::{
+    escape_gleam_string, find_type_entry, module_alias, GlossError, ImportEntry, TypeLookup,
+    TypeRegistry,
+};
 use std::collections::{BTreeMap, HashSet};
 
 pub(crate) struct DecoderOutput {
@@ -12,6 +19,58 @@ pub(crate) struct DecoderOutput {
     pub uses_option_helpers: bool,
 }
 
+/// State shared by the functions generating a single decoder
+struct DecoderContext<'a> {
+    backend: &'a dyn DecoderBackend,
+    /// Name the backend was requested by, shared by every type it may call into
+    backend_name: &'a str,
+    config: &'a Config,
+    registry: &'a TypeRegistry,
+    imports: &'a mut BTreeMap<String, ImportEntry>,
+    /// Module that type names and function references are resolved against
+    current_module_path: &'a str,
+    /// Module the decoder is written into; differs from `current_module_path` while the
+    /// fields of a flattened record are inlined
+    output_module_path: &'a str,
+    /// Import aliases of the module declaring the type, used to resolve default expressions
+    module_imports: &'a BTreeMap<String, String>,
+    /// Type parameters of the type being decoded; each one is received as a decoder argument
+    type_parameters: &'a [String],
+    /// Cycle the type being decoded belongs to, if any
+    recursion_group: Option<usize>,
+    /// Annotated types, used to build values for fields that are not decoded
+    type_lookup: &'a TypeLookup,
+    /// Fail on keys that no field reads
+    deny_unknown_fields: bool,
+    /// Tag key sharing the object with the variant fields, if any
+    tag_field: Option<String>,
+    /// Name of the private function converting captured `extra` values to `json.Json`
+    extra_value_helper: String,
+    uses_extra_value_helper: bool,
+    uses_option_helpers: bool,
+}
+
+/// State shared by the functions generating a single encoder
+struct EncoderContext<'a> {
+    backend: &'a dyn EncoderBackend,
+    encoder_type: &'a EncoderType,
+    config: &'a Config,
+    registry: &'a TypeRegistry,
+    /// Annotated types, used to inline the fields of flattened records
+    type_lookup: &'a TypeLookup,
+    imports: &'a mut BTreeMap<String, ImportEntry>,
+    /// Module that type names and function references are resolved against
+    current_module_path: &'a str,
+    /// Module the encoder is written into, which decides qualification and imports; differs
+    /// from `current_module_path` while the fields of a flattened record are inlined
+    output_module_path: &'a str,
+    /// Name of the private function converting captured `extra` values to `json.Json`
+    extra_value_helper: String,
+    uses_extra_value_helper: bool,
+    /// Type parameters of the type being encoded; each one is received as an encoder function argument
+    type_parameters: &'a [String],
+}
+
 pub(crate) fn generate_decoder(
     type_info: &CustomTypeInfo,
     config: &Config,
@@ -19,70 +78,163 @@ pub(crate) fn generate_decoder(
     imports: &mut BTreeMap<String, ImportEntry>,
     type_lookup: &TypeLookup,
     unknown_variant_message: Option<&str>,
+    backend: &dyn DecoderBackend,
 ) -> Result<DecoderOutput> {
     let type_name = &type_info.name;
     let decoder_name = config.fn_naming.render_decoder_fn_name(type_name);
 
     // Determine encoding mode based on constructors and type_info settings
-    let mode = determine_encoding_mode(&type_info.constructors, type_info);
+    let mode = determine_encoding_mode(&type_info.constructors, type_info, config);
+    let tagging = Tagging::for_type(type_info, config);
+    ensure_unique_variant_tags(type_info, &tagging)?;
+    let fallback = fallback_variant(type_info, mode)?;
 
     let field_naming = type_info
         .field_naming_strategy
         .unwrap_or(config.field_naming_strategy);
+    ensure_unique_object_keys(
+        type_info,
+        mode,
+        &tagging,
+        field_naming,
+        config,
+        registry,
+        type_lookup,
+    )?;
 
-    let mut uses_option_helpers = false;
+    let mut ctx = DecoderContext {
+        backend,
+        backend_name: type_info
+            .decoder_backend
+            .as_deref()
+            .unwrap_or(DynamicDecoderBackend::NAME),
+        config,
+        registry,
+        imports,
+        current_module_path: &type_info.module_path,
+        output_module_path: &type_info.module_path,
+        module_imports: &type_info.module_imports,
+        type_parameters: &type_info.parameters,
+        recursion_group: registry
+            .get(&type_info.module_path)
+            .and_then(|types| types.get(&type_info.name))
+            .and_then(|entry| entry.recursion_group),
+        type_lookup,
+        deny_unknown_fields: type_info.deny_unknown_fields
+            || config.deny_unknown_fields.unwrap_or_default(),
+        tag_field: (mode == EncodingMode::ObjectWithTypeTag).then(|| tagging.tag_field.clone()),
+        extra_value_helper: extra_value_helper_name(type_info),
+        uses_extra_value_helper: false,
+        uses_option_helpers: false,
+    };
 
-    let body = if type_info.constructors.len() == 1 {
+    let fallback_decoder = fallback
+        .map(|(constructor, payload)| generate_fallback_decoder(constructor, payload, backend))
+        .transpose()?;
+    let body = if type_info.int_enum {
+        let default_value_expr = default_value_for_type(
+            type_info,
+            type_lookup,
+            ctx.imports,
+            &mut ctx.uses_option_helpers,
+        );
+        generate_int_enum_decoder(
+            type_info,
+            &default_value_expr,
+            unknown_variant_message,
+            fallback_decoder.as_deref(),
+            backend,
+        )?
+    } else if type_info.constructors.len() == 1 {
         // Single constructor
         generate_single_constructor_decoder(
             &type_info.constructors[0],
             mode,
             field_naming,
-            config,
-            registry,
-            imports,
-            &type_info.module_path,
             0,
-            &mut uses_option_helpers,
+            &mut ctx,
+        )?
+    } else if matches!(
+        mode,
+        EncodingMode::ExternallyTagged | EncodingMode::ObjectWithNoTypeTag
+    ) {
+        // Multiple constructors without a tag to dispatch on
+        generate_one_of_decoder(
+            &type_info.constructors,
+            mode,
+            field_naming,
+            &tagging,
+            fallback_decoder.as_deref(),
+            &mut ctx,
         )?
     } else {
         // Multiple constructors
-        let type_tag_field = type_info.type_tag_field.as_deref().unwrap_or("type");
-        let default_value_expr =
-            default_value_for_type(type_info, type_lookup, imports, &mut uses_option_helpers);
-        let expected_variants = format_expected_variants(&type_info.constructors);
+        let default_value_expr = default_value_for_type(
+            type_info,
+            type_lookup,
+            ctx.imports,
+            &mut ctx.uses_option_helpers,
+        );
+        let expected_variants = format_expected_variants(&type_info.constructors, &tagging);
         let expected_message =
             format_unknown_variant_message(type_name, unknown_variant_message, &expected_variants);
         generate_multi_constructor_decoder(
             &type_info.constructors,
             mode,
             field_naming,
-            type_tag_field,
-            config,
-            registry,
-            imports,
-            &type_info.module_path,
+            &tagging,
             &default_value_expr,
             &expected_message,
-            &mut uses_option_helpers,
+            fallback_decoder.as_deref(),
+            &mut ctx,
         )?
     };
 
+    let parameters = type_info
+        .parameters
+        .iter()
+        .map(|param| {
+            format!(
+                "{}: {}",
+                type_param_decoder_name(param),
+                backend.decoder_type(param)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut code = format!(
+        "pub fn {}({}) -> {} {}",
+        decoder_name,
+        parameters,
+        backend.decoder_type(&applied_type_name(type_info)),
+        body
+    );
+    if ctx.uses_extra_value_helper {
+        let json = ensure_stdlib_import(ctx.imports, "gleam/json");
+        let json_type = format!("{}.Json", json);
+        code.push_str("\n\n");
+        code.push_str(&extra_value_helper(
+            &ctx.extra_value_helper,
+            &json,
+            &json_type,
+            ctx.imports,
+        ));
+    }
+
     Ok(DecoderOutput {
-        code: format!(
-            "pub fn {}() -> decode.Decoder({}) {}",
-            decoder_name, type_name, body
-        ),
-        uses_option_helpers,
+        code,
+        uses_option_helpers: ctx.uses_option_helpers,
     })
 }
 
 pub(crate) fn generate_encoder(
     type_info: &CustomTypeInfo,
-    encoder_type: EncoderType,
+    encoder_type: &EncoderType,
     config: &Config,
     registry: &TypeRegistry,
     imports: &mut BTreeMap<String, ImportEntry>,
+    type_lookup: &TypeLookup,
     backend: &dyn EncoderBackend,
 ) -> Result<String> {
     let type_name = &type_info.name;
@@ -95,7 +247,7 @@ pub(crate) fn generate_encoder(
         .fn_naming
         .encoder_function_naming
         .contains("{backend");
-    let mut unique_backend_ids: Vec<&'static str> = Vec::new();
+    let mut unique_backend_ids: Vec<&str> = Vec::new();
     for enc in &type_info.encoders {
         let id = enc.identifier();
         if !unique_backend_ids.contains(&id) {
@@ -107,27 +259,50 @@ pub(crate) fn generate_encoder(
     }
     let arg_name = function_name.clone();
     // Determine encoding mode based on constructors and type_info settings
-    let mode = determine_encoding_mode(&type_info.constructors, type_info);
+    let mode = determine_encoding_mode(&type_info.constructors, type_info, config);
+    let tagging = Tagging::for_type(type_info, config);
+    ensure_unique_variant_tags(type_info, &tagging)?;
+    let fallback = fallback_variant(type_info, mode)?;
 
     let field_naming = type_info
         .field_naming_strategy
         .unwrap_or(config.field_naming_strategy);
-    let type_tag_field = type_info.type_tag_field.as_deref().unwrap_or("type");
+    ensure_unique_object_keys(
+        type_info,
+        mode,
+        &tagging,
+        field_naming,
+        config,
+        registry,
+        type_lookup,
+    )?;
+
+    let mut ctx = EncoderContext {
+        backend,
+        encoder_type,
+        config,
+        registry,
+        type_lookup,
+        imports,
+        current_module_path: &type_info.module_path,
+        output_module_path: &type_info.module_path,
+        extra_value_helper: extra_value_helper_name(type_info),
+        uses_extra_value_helper: false,
+        type_parameters: &type_info.parameters,
+    };
 
-    let body = if type_info.constructors.len() == 1 {
+    let body = if type_info.int_enum {
+        generate_int_enum_encoder(type_info, &arg_name, backend)?
+    } else if type_info.constructors.len() == 1 {
         // Single constructor
         generate_single_constructor_encoder(
             &type_info.constructors[0],
             &arg_name,
             mode,
             field_naming,
-            type_tag_field,
-            encoder_type,
-            backend,
-            registry,
-            imports,
-            &type_info.module_path,
+            &tagging,
             2,
+            &mut ctx,
         )?
     } else {
         // Multiple constructors
@@ -136,23 +311,61 @@ pub(crate) fn generate_encoder(
             &arg_name,
             mode,
             field_naming,
-            type_tag_field,
-            encoder_type,
-            backend,
-            registry,
-            imports,
-            &type_info.module_path,
+            &tagging,
+            fallback.map(|(_, payload)| payload),
+            &mut ctx,
         )?
     };
 
-    Ok(format!(
-        "pub fn {}({}: {}) -> {} {{\n{}\n}}",
+    let mut parameters = vec![format!("{}: {}", arg_name, applied_type_name(type_info))];
+    for param in &type_info.parameters {
+        parameters.push(format!(
+            "{}: fn({}) -> {}",
+            type_param_encoder_name(param),
+            param,
+            backend.return_type()
+        ));
+    }
+
+    let mut code = format!(
+        "pub fn {}({}) -> {} {{\n{}\n}}",
         encoder_name,
-        arg_name,
-        type_name,
+        parameters.join(", "),
         backend.return_type(),
         body
-    ))
+    );
+    // The helper is only requested once the backend is known to expose a `gleam/json` API,
+    // whose module the backend already imports
+    if let (true, Some((_, json))) = (ctx.uses_extra_value_helper, backend.json_module()) {
+        code.push_str("\n\n");
+        code.push_str(&extra_value_helper(
+            &ctx.extra_value_helper,
+            json,
+            &backend.return_type(),
+            ctx.imports,
+        ));
+    }
+
+    Ok(code)
+}
+
+/// The type as written in generated signatures, e.g. `Page(item)`
+fn applied_type_name(type_info: &CustomTypeInfo) -> String {
+    if type_info.parameters.is_empty() {
+        type_info.name.clone()
+    } else {
+        format!("{}({})", type_info.name, type_info.parameters.join(", "))
+    }
+}
+
+/// Name of the decoder argument received for a type parameter
+fn type_param_decoder_name(param: &str) -> String {
+    format!("{}_decoder", param)
+}
+
+/// Name of the encoder function argument received for a type parameter
+fn type_param_encoder_name(param: &str) -> String {
+    format!("{}_encoder", param)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -160,11 +373,14 @@ enum EncodingMode {
     PlainString,
     ObjectWithTypeTag,
     ObjectWithNoTypeTag,
+    AdjacentlyTagged,
+    ExternallyTagged,
 }
 
 fn determine_encoding_mode(
     constructors: &[ConstructorInfo],
     type_info: &CustomTypeInfo,
+    config: &Config,
 ) -> EncodingMode {
     // If type tags are disabled, don't use them
     if type_info.disable_type_tag {
@@ -174,181 +390,926 @@ fn determine_encoding_mode(
     match constructors {
         [constructor] if constructor.fields.is_empty() => EncodingMode::PlainString,
         [_constructor] => EncodingMode::ObjectWithNoTypeTag,
-        constructors if constructors.iter().all(|c| c.fields.is_empty()) => {
+        // The fallback variant may keep the unrecognised tag without making this an object
+        constructors
+            if constructors
+                .iter()
+                .all(|c| c.fields.is_empty() || c.fallback) =>
+        {
             EncodingMode::PlainString
         }
-        _ => EncodingMode::ObjectWithTypeTag,
+        _ => match type_info
+            .representation
+            .or(config.enum_representation)
+            .unwrap_or_default()
+        {
+            EnumRepresentation::Internal => EncodingMode::ObjectWithTypeTag,
+            EnumRepresentation::Adjacent => EncodingMode::AdjacentlyTagged,
+            EnumRepresentation::External => EncodingMode::ExternallyTagged,
+            EnumRepresentation::Untagged => EncodingMode::ObjectWithNoTypeTag,
+        },
+    }
+}
+
+/// How variants of a type are told apart on the wire
+struct Tagging {
+    tag_field: String,
+    content_field: String,
+    variant_naming: FieldNamingConvention,
+}
+
+impl Tagging {
+    fn for_type(type_info: &CustomTypeInfo, config: &Config) -> Self {
+        Self {
+            tag_field: type_info
+                .type_tag_field
+                .clone()
+                .or_else(|| config.type_tag_field.clone())
+                .unwrap_or_else(|| "type".to_string()),
+            content_field: type_info
+                .content_field
+                .clone()
+                .or_else(|| config.content_field.clone())
+                .unwrap_or_else(|| "data".to_string()),
+            variant_naming: type_info
+                .variant_naming_strategy
+                .or(config.variant_naming_strategy)
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Pair every variant of an `int_enum` type with its integer, counting up from the
+/// previous value when no explicit `value` is given.
+fn int_enum_values(type_info: &CustomTypeInfo) -> Result<Vec<(&ConstructorInfo, i64)>> {
+    let mut values: Vec<(&ConstructorInfo, i64)> = Vec::new();
+    let mut next = 0;
+    for constructor in &type_info.constructors {
+        if !constructor.fields.is_empty() {
+            return Err(GlossError::GenerationError(format!(
+                "`int_enum` type `{}` has variant `{}` with fields; only fieldless variants can be written as integers",
+                type_info.name, constructor.name
+            )));
+        }
+
+        let value = constructor.value.unwrap_or(next);
+        if let Some((previous, _)) = values.iter().find(|(_, existing)| *existing == value) {
+            return Err(GlossError::GenerationError(format!(
+                "Variants `{}` and `{}` of type `{}` both use the value {}",
+                previous.name, constructor.name, type_info.name, value
+            )));
+        }
+        values.push((constructor, value));
+        next = value + 1;
     }
+    Ok(values)
+}
+
+fn generate_int_enum_decoder(
+    type_info: &CustomTypeInfo,
+    default_value_expr: &str,
+    unknown_variant_message: Option<&str>,
+    fallback_decoder: Option<&str>,
+    backend: &dyn DecoderBackend,
+) -> Result<String> {
+    let values = int_enum_values(type_info)?;
+
+    let cases: Vec<String> = values
+        .iter()
+        .filter(|(constructor, _)| !constructor.skip && !constructor.fallback)
+        .map(|(constructor, value)| {
+            format!("    {} -> {}", value, backend.success(&constructor.name))
+        })
+        .collect();
+
+    let expected: Vec<String> = values
+        .iter()
+        .filter(|(constructor, _)| !constructor.skip && !constructor.deprecated)
+        .map(|(_, value)| value.to_string())
+        .collect();
+    let expected_variants = match expected.len() {
+        0 => "value".to_string(),
+        1 => expected[0].clone(),
+        _ => format!("one of {}", expected.join(", ")),
+    };
+    let expected_message =
+        format_unknown_variant_message(&type_info.name, unknown_variant_message, &expected_variants);
+
+    let unknown_branch = fallback_decoder.map(str::to_string).unwrap_or_else(|| {
+        backend.failure(
+            default_value_expr,
+            &format!("\"{}\"", escape_gleam_string(&expected_message)),
+        )
+    });
+
+    Ok(format!(
+        r#"{{
+  {}
+  case variant {{
+{}
+    _ -> {}
+  }}
+}}"#,
+        backend.then("variant", &backend.int()),
+        cases.join("\n"),
+        unknown_branch,
+    ))
+}
+
+fn generate_int_enum_encoder(
+    type_info: &CustomTypeInfo,
+    arg_name: &str,
+    backend: &dyn EncoderBackend,
+) -> Result<String> {
+    let cases: Vec<String> = int_enum_values(type_info)?
+        .into_iter()
+        .map(|(constructor, value)| {
+            format!(
+                "    {} -> {}",
+                constructor.name,
+                backend.encode_int(&value.to_string())
+            )
+        })
+        .collect();
+
+    Ok(format!("  case {} {{\n{}\n  }}", arg_name, cases.join("\n")))
 }
 
 fn generate_single_constructor_decoder(
     constructor: &ConstructorInfo,
     mode: EncodingMode,
     field_naming: FieldNamingConvention,
-    config: &Config,
-    registry: &TypeRegistry,
-    imports: &mut BTreeMap<String, ImportEntry>,
-    current_module_path: &str,
     nesting: usize,
-    uses_option_helpers: &mut bool,
+    ctx: &mut DecoderContext,
 ) -> Result<String> {
-    let constructor_name = &constructor.name;
+    // Records flattened from another module are built through that module's import
+    let constructor_name = if ctx.current_module_path == ctx.output_module_path {
+        constructor.name.clone()
+    } else {
+        let alias = ensure_import(ctx.imports, ctx.current_module_path);
+        format!("{}.{}", alias, constructor.name)
+    };
 
     if mode == EncodingMode::PlainString {
-        return Ok(format!("{{\n  decode.success({})\n}}", constructor_name));
+        return Ok(format!(
+            "{{\n  {}\n}}",
+            ctx.backend.success(&constructor_name)
+        ));
     }
 
-    if constructor.fields.is_empty() {
-        return Ok(format!("{{\n  decode.success({})\n}}", constructor_name));
+    // An `extra` field captures unknown keys, so there is nothing left to reject.
+    // Fieldless adjacently tagged variants have no content object to check.
+    let captures_extra = constructor.fields.iter().any(|f| f.extra);
+    let check_keys = ctx.deny_unknown_fields
+        && !captures_extra
+        && !(mode == EncodingMode::AdjacentlyTagged && constructor.fields.is_empty());
+
+    if constructor.fields.is_empty() && !check_keys {
+        return Ok(format!(
+            "{{\n  {}\n}}",
+            ctx.backend.success(&constructor_name)
+        ));
+    }
+
+    let mut known_keys: Vec<String> = ctx.tag_field.iter().cloned().collect();
+    if check_keys || captures_extra {
+        let module_path = ctx.current_module_path;
+        collect_decoded_keys(&constructor.fields, field_naming, module_path, &mut known_keys, ctx)?;
     }
 
     let mut field_decoders = Vec::new();
+    if check_keys {
+        field_decoders.push(unknown_fields_check(&known_keys, nesting + 2, ctx)?);
+    }
     for field in &constructor.fields {
-        let field_decoder = generate_field_decoder(
-            field,
-            field_naming,
-            config,
-            registry,
-            imports,
-            current_module_path,
-            nesting + 2,
-            uses_option_helpers,
-        )?;
+        let field_decoder = if field.extra {
+            generate_extra_field_decoder(field, &known_keys, nesting + 2, ctx)?
+        } else {
+            generate_field_decoder(field, field_naming, nesting + 2, ctx)?
+        };
         field_decoders.push(field_decoder);
     }
 
-    let field_names: Vec<String> = constructor
-        .fields
-        .iter()
-        .map(|f| format!("{}:", f.label))
-        .collect();
+    let constructor_expr = if constructor.fields.is_empty() {
+        constructor_name
+    } else {
+        let field_names: Vec<String> = constructor
+            .fields
+            .iter()
+            .map(|f| format!("{}:", f.label))
+            .collect();
+        format!("{}({})", constructor_name, field_names.join(", "))
+    };
 
     let decoders = field_decoders.join("\n");
     let indent = " ".repeat(nesting);
 
     Ok(format!(
-        "{{\n{}\n{}  decode.success({}({}))\n{}}}",
+        "{{\n{}\n{}  {}\n{}}}",
         decoders,
         indent,
-        constructor_name,
-        field_names.join(", "),
+        ctx.backend.success(&constructor_expr),
         indent
     ))
 }
 
+/// Keys read by the decoder of `fields`, including aliases and flattened records
+fn collect_decoded_keys(
+    fields: &[FieldInfo],
+    field_naming: FieldNamingConvention,
+    module_path: &str,
+    keys: &mut Vec<String>,
+    ctx: &DecoderContext,
+) -> Result<()> {
+    for field in fields.iter().filter(|f| !f.skip_decoding && !f.extra) {
+        if field.flatten {
+            let nested = flattened_type(field, module_path, ctx.registry, ctx.type_lookup)?;
+            let nested_naming = nested
+                .field_naming_strategy
+                .unwrap_or(ctx.config.field_naming_strategy);
+            collect_decoded_keys(
+                &nested.constructors[0].fields,
+                nested_naming,
+                &nested.module_path,
+                keys,
+                ctx,
+            )?;
+            continue;
+        }
+
+        // A nested path claims its whole top-level key
+        let key = field_key_path(field, field_naming).remove(0);
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+        keys.extend(field.aliases.iter().cloned());
+    }
+
+    Ok(())
+}
+
+/// Value type of a field annotated `extra`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExtraValues {
+    Dynamic,
+    Json,
+}
+
+fn extra_values(field: &FieldInfo) -> Result<ExtraValues> {
+    if let TypeExpression::Constructor {
+        module,
+        name,
+        arguments,
+    } = &field.type_expr
+    {
+        if is_standard_dict(module.as_deref(), name, arguments) {
+            if let (
+                TypeExpression::Constructor {
+                    module: None,
+                    name: key,
+                    ..
+                },
+                TypeExpression::Constructor {
+                    module: value_module,
+                    name: value,
+                    ..
+                },
+            ) = (&arguments[0], &arguments[1])
+            {
+                match (key.as_str(), value_module.as_deref(), value.as_str()) {
+                    ("String", Some("gleam/dynamic"), "Dynamic") => {
+                        return Ok(ExtraValues::Dynamic)
+                    }
+                    ("String", Some("gleam/json"), "Json") => return Ok(ExtraValues::Json),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Err(GlossError::GenerationError(format!(
+        "Field `{}` is marked `extra` but has type `{}`. Use `Dict(String, Dynamic)` or `Dict(String, json.Json)`.",
+        field.label, field.type_
+    )))
+}
+
+/// Decoder step collecting every key outside `known_keys` into an `extra` field
+fn generate_extra_field_decoder(
+    field: &FieldInfo,
+    known_keys: &[String],
+    nesting: usize,
+    ctx: &mut DecoderContext,
+) -> Result<String> {
+    let backend = ctx.backend;
+    let dynamic = dynamic_decoder(backend, "`extra` fields")?;
+    let value_decoder = match extra_values(field)? {
+        ExtraValues::Dynamic => dynamic,
+        ExtraValues::Json => {
+            ctx.uses_extra_value_helper = true;
+            backend.map(&dynamic, &ctx.extra_value_helper)
+        }
+    };
+    let object_decoder = string_dict_decoder(backend, &value_decoder, "`extra` fields")?;
+    let dict_alias = ensure_stdlib_import(ctx.imports, "gleam/dict");
+    let remaining = backend.map(
+        &object_decoder,
+        &format!(
+            "fn(object) {{ {}.drop(object, [{}]) }}",
+            dict_alias,
+            gleam_string_list(known_keys)
+        ),
+    );
+
+    Ok(format!(
+        "{}{}",
+        " ".repeat(nesting),
+        backend.then(&field.label, &remaining)
+    ))
+}
+
+/// Decoder for untyped values, which some features need to read the whole object
+fn dynamic_decoder(backend: &dyn DecoderBackend, feature: &str) -> Result<String> {
+    backend.dynamic().ok_or_else(|| {
+        GlossError::GenerationError(format!(
+            "Decoder backend `{}` cannot decode `Dynamic` values, which {} need",
+            backend.name(),
+            feature
+        ))
+    })
+}
+
+/// Decoder step failing unless the input is an array of `length` elements
+fn tuple_length_check(length: usize, ctx: &mut DecoderContext) -> Result<String> {
+    let backend = ctx.backend;
+    let elements = backend.list(&dynamic_decoder(backend, "tuple fields")?);
+    let list_alias = ensure_stdlib_import(ctx.imports, "gleam/list");
+    let check = format!(
+        "fn(elements) {{ case {}.length(elements) == {} {{ True -> {} False -> {} }} }}",
+        list_alias,
+        length,
+        backend.success("Nil"),
+        backend.failure("Nil", &format!("\"expected array of length {}\"", length))
+    );
+    Ok(backend.then("_", &backend.and_then(&elements, &check)))
+}
+
+fn string_dict_decoder(backend: &dyn DecoderBackend, inner: &str, feature: &str) -> Result<String> {
+    backend.string_dict(inner).ok_or_else(|| {
+        GlossError::GenerationError(format!(
+            "Decoder backend `{}` cannot decode objects with arbitrary keys, which {} need",
+            backend.name(),
+            feature
+        ))
+    })
+}
+
+fn gleam_string_list(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|value| format!("\"{}\"", escape_gleam_string(value)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn extra_value_helper_name(type_info: &CustomTypeInfo) -> String {
+    format!("{}_extra_value_to_json", to_snake_case(&type_info.name))
+}
+
+/// Private function turning an arbitrary dynamic value into `json_type`, used for `extra`
+/// fields whose values have to change representation. `json` is the alias of a module
+/// implementing the `gleam/json` API.
+fn extra_value_helper(
+    name: &str,
+    json: &str,
+    json_type: &str,
+    imports: &mut BTreeMap<String, ImportEntry>,
+) -> String {
+    let decode = ensure_stdlib_import(imports, "gleam/dynamic/decode");
+    let dynamic = ensure_stdlib_import(imports, "gleam/dynamic");
+    let dict = ensure_stdlib_import(imports, "gleam/dict");
+
+    format!(
+        r#"fn {name}(value: {dynamic}.Dynamic) -> {json_type} {{
+  let decoder =
+    {decode}.one_of({decode}.map({decode}.string, {json}.string), [
+      {decode}.map({decode}.bool, {json}.bool),
+      {decode}.map({decode}.int, {json}.int),
+      {decode}.map({decode}.float, {json}.float),
+      {decode}.map({decode}.list({decode}.map({decode}.dynamic, {name})), {json}.preprocessed_array),
+      {decode}.map({decode}.dict({decode}.string, {decode}.map({decode}.dynamic, {name})), fn(object) {{
+        {json}.object({dict}.to_list(object))
+      }}),
+    ])
+  case {decode}.run(value, decoder) {{
+    Ok(json_value) -> json_value
+    Error(_) -> {json}.null()
+  }}
+}}"#
+    )
+}
+
+/// Decoder step failing when the object holds keys outside `known_keys`
+fn unknown_fields_check(
+    known_keys: &[String],
+    nesting: usize,
+    ctx: &mut DecoderContext,
+) -> Result<String> {
+    let backend = ctx.backend;
+    let indent = " ".repeat(nesting);
+    let feature = "`deny_unknown_fields` types";
+    let object_decoder =
+        string_dict_decoder(backend, &dynamic_decoder(backend, feature)?, feature)?;
+    let dict_alias = ensure_stdlib_import(ctx.imports, "gleam/dict");
+    let list_alias = ensure_stdlib_import(ctx.imports, "gleam/list");
+    let string_alias = ensure_stdlib_import(ctx.imports, "gleam/string");
+    let known = gleam_string_list(known_keys);
+
+    let check = format!(
+        r#"fn(object) {{
+{indent}  case {list}.filter({dict}.keys(object), fn(key) {{ !{list}.contains([{known}], key) }}) {{
+{indent}    [] -> {success}
+{indent}    unknown -> {failure}
+{indent}  }}
+{indent}}}"#,
+        indent = indent,
+        list = list_alias,
+        dict = dict_alias,
+        known = known,
+        success = backend.success("Nil"),
+        failure = backend.failure(
+            "Nil",
+            &format!(
+                r#""known fields only (unexpected: " <> {}.join(unknown, ", ") <> ")""#,
+                string_alias
+            )
+        ),
+    );
+
+    Ok(format!(
+        "{}{}",
+        indent,
+        backend.then("_", &backend.and_then(&object_decoder, &check))
+    ))
+}
+
 fn generate_multi_constructor_decoder(
     constructors: &[ConstructorInfo],
     mode: EncodingMode,
     field_naming: FieldNamingConvention,
-    type_tag_field: &str,
-    config: &Config,
-    registry: &TypeRegistry,
-    imports: &mut BTreeMap<String, ImportEntry>,
-    current_module_path: &str,
+    tagging: &Tagging,
     default_value_expr: &str,
     expected_message: &str,
-    uses_option_helpers: &mut bool,
+    fallback_decoder: Option<&str>,
+    ctx: &mut DecoderContext,
 ) -> Result<String> {
+    let backend = ctx.backend;
     let discriminant = if mode == EncodingMode::PlainString {
-        "use variant <- decode.then(decode.string)".to_string()
+        backend.then("variant", &backend.string())
     } else {
-        format!(
-            r#"use variant <- decode.field("{}", decode.string)"#,
-            type_tag_field
-        )
+        backend.field("variant", &tagging.tag_field, &backend.string())
     };
 
     let mut cases = Vec::new();
-    for constructor in constructors {
-        let tag = to_snake_case(&constructor.name);
-        let body = generate_single_constructor_decoder(
-            constructor,
-            mode,
-            field_naming,
-            config,
-            registry,
-            imports,
-            current_module_path,
-            4,
-            uses_option_helpers,
-        )?;
-        cases.push(format!(r#"    "{}" -> {}"#, tag, body.trim()));
+    for constructor in constructors.iter().filter(|c| !c.skip && !c.fallback) {
+        let tag = escape_gleam_string(&variant_tag(constructor, tagging));
+        let body = generate_single_constructor_decoder(constructor, mode, field_naming, 4, ctx)?;
+        let body = if mode == EncodingMode::AdjacentlyTagged && !constructor.fields.is_empty() {
+            // Adjacently tagged variants read their fields from the content key
+            backend.at(&[tagging.content_field.clone()], body.trim())
+        } else {
+            body.trim().to_string()
+        };
+        cases.push(format!(r#"    "{}" -> {}"#, tag, body));
     }
 
     let cases_str = cases.join("\n");
-    let failure_message = escape_gleam_string(expected_message);
+    let unknown_branch = fallback_decoder.map(str::to_string).unwrap_or_else(|| {
+        backend.failure(
+            default_value_expr,
+            &format!("\"{}\"", escape_gleam_string(expected_message)),
+        )
+    });
 
     Ok(format!(
         r#"{{
   {}
   case variant {{
 {}
-    _ -> decode.failure({}, "{}")
+    _ -> {}
   }}
 }}"#,
-        discriminant, cases_str, default_value_expr, failure_message,
+        discriminant, cases_str, unknown_branch,
     ))
 }
 
-fn generate_field_decoder(
-    field: &FieldInfo,
+/// Decoder for representations without a tag field: externally tagged variants are
+/// tried by key, untagged variants by shape, in declaration order.
+fn generate_one_of_decoder(
+    constructors: &[ConstructorInfo],
+    mode: EncodingMode,
     field_naming: FieldNamingConvention,
-    config: &Config,
-    registry: &TypeRegistry,
-    imports: &mut BTreeMap<String, ImportEntry>,
-    current_module_path: &str,
-    nesting: usize,
-    uses_option_helpers: &mut bool,
+    tagging: &Tagging,
+    fallback_decoder: Option<&str>,
+    ctx: &mut DecoderContext,
 ) -> Result<String> {
-    // Use custom name if provided, otherwise convert using naming convention
-    let json_field_name = match &field.custom_name {
-        Some(name) => name.clone(),
-        None => convert_field_name(&field.label, field_naming),
-    };
-    let type_decoder = generate_type_decoder(
-        &field.type_expr,
-        field.decoder_with.as_deref(),
-        registry,
-        imports,
-        current_module_path,
-    )?;
-    let indent = " ".repeat(nesting);
+    let backend = ctx.backend;
+    let mut alternatives = Vec::new();
+    for constructor in constructors.iter().filter(|c| !c.skip && !c.fallback) {
+        let body = generate_single_constructor_decoder(constructor, mode, field_naming, 4, ctx)?;
+        let alternative = if mode == EncodingMode::ExternallyTagged {
+            backend.at(&[variant_tag(constructor, tagging)], body.trim())
+        } else {
+            body.trim().to_string()
+        };
+        alternatives.push(alternative);
+    }
+    // Tried last, once no variant matched the input
+    alternatives.extend(fallback_decoder.map(str::to_string));
 
-    // Determine if field should be optional or required
-    let is_optional_field = match field.marker {
-        FieldMarker::Optional => true,
-        FieldMarker::Required => false,
-        FieldMarker::Default => match config.absent_field_mode {
-            AbsentFieldMode::MaybeAbsent => field.is_option,
-            AbsentFieldMode::ErrorIfAbsent => false,
-        },
+    if alternatives.is_empty() {
+        return Err(GlossError::GenerationError(
+            "Cannot decode a type without decodable variants".to_string(),
+        ));
+    }
+    let first = alternatives.remove(0);
+
+    Ok(format!(
+        "{{\n  {}\n}}",
+        backend.one_of(&first, &alternatives)
+    ))
+}
+
+/// What the `fallback` variant of a type keeps from an input no other variant accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FallbackPayload {
+    /// Fieldless variant
+    Nothing,
+    /// Single `String` field receiving the unrecognised tag
+    Tag,
+    /// Single `Dynamic` field receiving the whole input
+    Raw,
+}
+
+/// The variant annotated `fallback`, if any, checked against the representation of the type
+fn fallback_variant(
+    type_info: &CustomTypeInfo,
+    mode: EncodingMode,
+) -> Result<Option<(&ConstructorInfo, FallbackPayload)>> {
+    let mut fallbacks = type_info.constructors.iter().filter(|c| c.fallback);
+    let constructor = match fallbacks.next() {
+        Some(constructor) => constructor,
+        None => return Ok(None),
     };
+    if let Some(other) = fallbacks.next() {
+        return Err(GlossError::GenerationError(format!(
+            "Variants `{}` and `{}` of type `{}` are both marked `fallback`",
+            constructor.name, other.name, type_info.name
+        )));
+    }
 
-    if is_optional_field {
-        // Field can be absent - use optional_field
-        *uses_option_helpers = true;
-        Ok(format!(
-            r#"{}use {} <- decode.optional_field("{}", option.None, {})"#,
-            indent, field.label, json_field_name, type_decoder
+    let invalid = |reason: &str| {
+        GlossError::GenerationError(format!(
+            "Fallback variant `{}` of type `{}` {}",
+            constructor.name, type_info.name, reason
         ))
+    };
+    if constructor.skip {
+        return Err(invalid("cannot be skipped"));
+    }
+    if type_info.constructors.len() < 2 {
+        return Err(invalid("needs at least one other variant"));
+    }
+
+    let payload = match constructor.fields.as_slice() {
+        [] => FallbackPayload::Nothing,
+        [field] => match &field.type_expr {
+            TypeExpression::Constructor {
+                module: None,
+                name,
+                arguments,
+            } if name == "String" && arguments.is_empty() => FallbackPayload::Tag,
+            TypeExpression::Constructor {
+                module,
+                name,
+                arguments,
+            } if name == "Dynamic"
+                && arguments.is_empty()
+                && module.as_deref() == Some("gleam/dynamic") =>
+            {
+                FallbackPayload::Raw
+            }
+            _ => {
+                return Err(invalid(&format!(
+                    "must hold the unrecognised tag as a `String` or the input as a `Dynamic`, not `{}`",
+                    field.type_
+                )))
+            }
+        },
+        _ => return Err(invalid("can have at most one field")),
+    };
+
+    let has_tag = matches!(
+        mode,
+        EncodingMode::PlainString
+            | EncodingMode::ObjectWithTypeTag
+            | EncodingMode::AdjacentlyTagged
+    );
+    if payload == FallbackPayload::Tag && !has_tag {
+        return Err(invalid(
+            "keeps the unrecognised tag, but externally tagged and untagged representations do not read one",
+        ));
+    }
+
+    Ok(Some((constructor, payload)))
+}
+
+/// Decoder building the fallback variant from the tag bound to `variant` or the raw input
+fn generate_fallback_decoder(
+    constructor: &ConstructorInfo,
+    payload: FallbackPayload,
+    backend: &dyn DecoderBackend,
+) -> Result<String> {
+    Ok(match payload {
+        FallbackPayload::Nothing => backend.success(&constructor.name),
+        FallbackPayload::Tag => backend.success(&fallback_construction(constructor, "variant")),
+        FallbackPayload::Raw => backend.map(
+            &dynamic_decoder(backend, "`Dynamic` fallback variants")?,
+            &format!(
+                "fn(value) {{ {} }}",
+                fallback_construction(constructor, "value")
+            ),
+        ),
+    })
+}
+
+/// Call of the single-field fallback constructor with `value`
+fn fallback_construction(constructor: &ConstructorInfo, value: &str) -> String {
+    match constructor.fields.first() {
+        Some(field) if !field.label.starts_with("_unlabeled") => {
+            format!("{}({}: {})", constructor.name, field.label, value)
+        }
+        _ => format!("{}({})", constructor.name, value),
+    }
+}
+
+fn generate_field_decoder<'a>(
+    field: &FieldInfo,
+    field_naming: FieldNamingConvention,
+    nesting: usize,
+    ctx: &mut DecoderContext<'a>,
+) -> Result<String> {
+    let indent = " ".repeat(nesting);
+
+    if field.skip_decoding {
+        let value = skipped_field_value(field, ctx)?;
+        return Ok(format!("{}let {} = {}", indent, field.label, value));
+    }
+
+    if field.flatten {
+        let type_lookup: &'a TypeLookup = ctx.type_lookup;
+        let nested = flattened_type(field, ctx.current_module_path, ctx.registry, type_lookup)?;
+        let nested_naming = nested
+            .field_naming_strategy
+            .unwrap_or(ctx.config.field_naming_strategy);
+
+        // The nested fields are read inline from the same object. Unknown keys are left to
+        // the parent, whose key list already includes them, so no check is made here.
+        let outer_module_path = ctx.current_module_path;
+        let outer_imports = ctx.module_imports;
+        let outer_keys = (ctx.deny_unknown_fields, ctx.tag_field.take());
+        ctx.current_module_path = &nested.module_path;
+        ctx.module_imports = &nested.module_imports;
+        ctx.deny_unknown_fields = false;
+        let result = generate_single_constructor_decoder(
+            &nested.constructors[0],
+            EncodingMode::ObjectWithNoTypeTag,
+            nested_naming,
+            nesting,
+            ctx,
+        );
+        ctx.current_module_path = outer_module_path;
+        ctx.module_imports = outer_imports;
+        (ctx.deny_unknown_fields, ctx.tag_field) = outer_keys;
+        return Ok(format!(
+            "{}{}",
+            indent,
+            ctx.backend.then(&field.label, &result?)
+        ));
+    }
+
+    // Use custom name if provided, otherwise convert using naming convention
+    let key_path = field_key_path(field, field_naming);
+    let type_decoder = generate_validated_decoder(field, ctx)?;
+
+    // An explicit default always makes the key optional
+    let absent_default = if let Some(default) = &field.default {
+        Some(resolve_field_default(default, ctx)?)
     } else {
-        // Field must be present - use field
-        Ok(format!(
-            r#"{}use {} <- decode.field("{}", {})"#,
-            indent, field.label, json_field_name, type_decoder
+        // Determine if field should be optional or required
+        let is_optional_field = match field.marker {
+            FieldMarker::Optional => true,
+            FieldMarker::Required => false,
+            FieldMarker::Default => match ctx.config.absent_field_mode {
+                AbsentFieldMode::MaybeAbsent => field.is_option,
+                AbsentFieldMode::ErrorIfAbsent => false,
+            },
+        };
+        if is_optional_field {
+            ctx.uses_option_helpers = true;
+            Some("option.None".to_string())
+        } else {
+            None
+        }
+    };
+
+    let backend = ctx.backend;
+    let step = if !field.aliases.is_empty() {
+        // Decode the first key that is present, so a present but invalid value still fails.
+        // When none is, use the default or report the primary key as missing.
+        let mut decoder = match absent_default {
+            Some(default_expr) => backend.success(&default_expr),
+            None => backend.at(&key_path, &type_decoder),
+        };
+        let keys = std::iter::once(key_path.clone())
+            .chain(field.aliases.iter().map(|alias| vec![alias.clone()]))
+            .collect::<Vec<_>>();
+        for path in keys.iter().rev() {
+            let present = backend.optionally_at(path, "False", &backend.success("True"));
+            decoder = backend.and_then(
+                &present,
+                &format!(
+                    "fn(present) {{ case present {{ True -> {} False -> {} }} }}",
+                    backend.at(path, &type_decoder),
+                    decoder
+                ),
+            );
+        }
+        backend.then(&field.label, &decoder)
+    } else if let [json_field_name] = key_path.as_slice() {
+        match absent_default {
+            // Field can be absent - use optional_field
+            Some(default_expr) => {
+                backend.optional_field(&field.label, json_field_name, &default_expr, &type_decoder)
+            }
+            // Field must be present - use field
+            None => backend.field(&field.label, json_field_name, &type_decoder),
+        }
+    } else {
+        // Nested path: any missing object along the way counts as an absent key
+        match absent_default {
+            Some(default_expr) => backend.then(
+                &field.label,
+                &backend.optionally_at(&key_path, &default_expr, &type_decoder),
+            ),
+            None => backend.subfield(&field.label, &key_path, &type_decoder),
+        }
+    };
+
+    Ok(format!("{}{}", indent, step))
+}
+
+/// Decoder for the value of a field, followed by the checks of its validation annotations.
+/// Constraints on an `Option(a)` field apply to the inner value.
+fn generate_validated_decoder(field: &FieldInfo, ctx: &mut DecoderContext) -> Result<String> {
+    let override_fn = field.decoder_with.as_deref();
+    if field.validation.is_empty() {
+        return generate_type_decoder(&field.type_expr, override_fn, ctx);
+    }
+
+    let inner_type = match &field.type_expr {
+        TypeExpression::Constructor { arguments, .. }
+            if override_fn.is_none() && field.type_expr.is_standard_option() =>
+        {
+            arguments.first()
+        }
+        _ => None,
+    };
+    let target_type = inner_type.unwrap_or(&field.type_expr);
+
+    let backend = ctx.backend;
+    let mut decoder = generate_type_decoder(target_type, override_fn, ctx)?;
+    for (condition, expected) in validation_checks(field, target_type, ctx)? {
+        let check = format!(
+            "fn(value) {{ case {} {{ True -> {} False -> {} }} }}",
+            condition,
+            backend.success("value"),
+            backend.failure("value", &format!("\"{}\"", escape_gleam_string(&expected)))
+        );
+        decoder = backend.and_then(&decoder, &check);
+    }
+
+    if let Some(validate_with) = &field.validation.validate_with {
+        let mut reference = parse_function_reference(validate_with)?;
+        reference
+            .module_path
+            .get_or_insert_with(|| ctx.current_module_path.to_string());
+        let path = render_function_path(&reference, ctx.imports, ctx.output_module_path);
+        let check = format!(
+            "fn(value) {{ case {}(value) {{ Ok(value) -> {} Error(message) -> {} }} }}",
+            path,
+            backend.success("value"),
+            backend.failure("value", "message")
+        );
+        decoder = backend.and_then(&decoder, &check);
+    }
+
+    if inner_type.is_some() {
+        decoder = backend.optional(&decoder);
+    }
+    Ok(decoder)
+}
+
+/// Boolean Gleam expressions over `value` paired with the expectation reported when they fail
+fn validation_checks(
+    field: &FieldInfo,
+    target_type: &TypeExpression,
+    ctx: &mut DecoderContext,
+) -> Result<Vec<(String, String)>> {
+    let validation = &field.validation;
+    let type_name = match target_type {
+        TypeExpression::Constructor { module: None, name, .. } => name.as_str(),
+        _ => "",
+    };
+    let unsupported = |constraint: &str| {
+        GlossError::GenerationError(format!(
+            "`{}` cannot be used on field `{}` of type `{}`",
+            constraint, field.label, field.type_
         ))
+    };
+
+    let mut checks = Vec::new();
+
+    for (bound, operator, expectation) in [
+        (&validation.min, ">=", "at least"),
+        (&validation.max, "<=", "at most"),
+    ] {
+        if let Some(bound) = bound {
+            let (operator, literal) = match type_name {
+                "Int" if !bound.contains('.') => (operator.to_string(), bound.clone()),
+                "Float" if bound.contains('.') => (format!("{}.", operator), bound.clone()),
+                "Float" => (format!("{}.", operator), format!("{}.0", bound)),
+                _ => return Err(unsupported(if operator == ">=" { "min" } else { "max" })),
+            };
+            checks.push((
+                format!("value {} {}", operator, literal),
+                format!("a number {} {}", expectation, bound),
+            ));
+        }
+    }
+
+    for (length, operator, expectation) in [
+        (&validation.min_length, ">=", "at least"),
+        (&validation.max_length, "<=", "at most"),
+    ] {
+        if let Some(length) = length {
+            let module = match type_name {
+                "String" => ensure_stdlib_import(ctx.imports, "gleam/string"),
+                "List" => ensure_stdlib_import(ctx.imports, "gleam/list"),
+                _ => {
+                    return Err(unsupported(if operator == ">=" {
+                        "min_length"
+                    } else {
+                        "max_length"
+                    }))
+                }
+            };
+            checks.push((
+                format!("{}.length(value) {} {}", module, operator, length),
+                format!("a length of {} {}", expectation, length),
+            ));
+        }
     }
+
+    if !validation.one_of.is_empty() {
+        if type_name != "String" {
+            return Err(unsupported("one_of"));
+        }
+        let list_alias = ensure_stdlib_import(ctx.imports, "gleam/list");
+        checks.push((
+            format!(
+                "{}.contains([{}], value)",
+                list_alias,
+                gleam_string_list(&validation.one_of)
+            ),
+            format!("one of {}", validation.one_of.join(", ")),
+        ));
+    }
+
+    Ok(checks)
 }
 
 fn generate_type_decoder(
     type_expr: &TypeExpression,
     override_fn: Option<&str>,
-    registry: &TypeRegistry,
-    imports: &mut BTreeMap<String, ImportEntry>,
-    current_module_path: &str,
+    ctx: &mut DecoderContext,
 ) -> Result<String> {
     if let Some(override_path) = override_fn {
-        return resolve_decoder_override(override_path, imports, current_module_path);
+        return resolve_decoder_override(
+            override_path,
+            ctx.imports,
+            ctx.current_module_path,
+            ctx.output_module_path,
+        );
     }
 
     match type_expr {
@@ -363,38 +1324,32 @@ fn generate_type_decoder(
                 && module.as_deref() == Some("gleam/option")
                 && !arguments.is_empty()
             {
-                let inner = generate_type_decoder(
-                    &arguments[0],
-                    None,
-                    registry,
-                    imports,
-                    current_module_path,
-                )?;
-                return Ok(format!("decode.optional({})", inner));
+                let inner = generate_type_decoder(&arguments[0], None, ctx)?;
+                return Ok(ctx.backend.optional(&inner));
             }
 
             if name_str == "List" && !arguments.is_empty() {
-                let inner = generate_type_decoder(
-                    &arguments[0],
-                    None,
-                    registry,
-                    imports,
-                    current_module_path,
-                )?;
-                return Ok(format!("decode.list({})", inner));
+                let inner = generate_type_decoder(&arguments[0], None, ctx)?;
+                return Ok(ctx.backend.list(&inner));
+            }
+
+            if is_standard_dict(module.as_deref(), name_str, arguments) {
+                ensure_string_dict_key(&arguments[0])?;
+                let inner = generate_type_decoder(&arguments[1], None, ctx)?;
+                return string_dict_decoder(ctx.backend, &inner, "`Dict` fields");
             }
 
             match name_str {
-                "String" => Ok("decode.string".to_string()),
-                "Int" => Ok("decode.int".to_string()),
-                "Float" => Ok("decode.float".to_string()),
-                "Bool" => Ok("decode.bool".to_string()),
+                "String" => Ok(ctx.backend.string()),
+                "Int" => Ok(ctx.backend.int()),
+                "Float" => Ok(ctx.backend.float()),
+                "Bool" => Ok(ctx.backend.bool()),
                 _ => {
                     if let Some(entry) = find_type_entry(
-                        registry,
+                        ctx.registry,
                         module.as_deref(),
                         name,
-                        current_module_path,
+                        ctx.current_module_path,
                     ) {
                         if !entry.generates_decoder {
                             return Err(GlossError::GenerationError(format!(
@@ -402,17 +1357,46 @@ fn generate_type_decoder(
                                 name
                             )));
                         }
+                        if entry.decoder_backend != ctx.backend_name {
+                            return Err(GlossError::GenerationError(format!(
+                                "Decoder for type `{}` is generated with backend `{}`, which cannot be used from a `{}` decoder. Provide `decoder_with` override.",
+                                name, entry.decoder_backend, ctx.backend_name
+                            )));
+                        }
+                        ensure_type_argument_count(name, entry.parameters.len(), arguments.len())?;
 
                         let decoder_name = entry
                             .decoder_fn_name
                             .as_ref()
                             .cloned()
                             .unwrap_or_else(|| format!("{}_decoder", to_snake_case(name)));
-                        if entry.module_path == current_module_path {
-                            Ok(format!("{}()", decoder_name))
+
+                        // Generic types receive a decoder for each type argument
+                        let mut argument_decoders = Vec::new();
+                        for argument in arguments {
+                            argument_decoders.push(generate_type_decoder(argument, None, ctx)?);
+                        }
+
+                        let call = if entry.module_path == ctx.output_module_path {
+                            format!("{}({})", decoder_name, argument_decoders.join(", "))
                         } else {
-                            let alias = ensure_import(imports, &entry.module_path);
-                            Ok(format!("{}.{decoder_name}()", alias, decoder_name = decoder_name))
+                            let alias = ensure_import(ctx.imports, &entry.module_path);
+                            format!(
+                                "{}.{decoder_name}({})",
+                                alias,
+                                argument_decoders.join(", "),
+                                decoder_name = decoder_name
+                            )
+                        };
+
+                        // Building a decoder for a type in the same cycle would recurse forever,
+                        // so those references are deferred until decoding actually reaches them
+                        if entry.recursion_group.is_some()
+                            && entry.recursion_group == ctx.recursion_group
+                        {
+                            Ok(ctx.backend.recursive(&call))
+                        } else {
+                            Ok(call)
                         }
                     } else {
                         Err(GlossError::GenerationError(format!(
@@ -423,11 +1407,34 @@ fn generate_type_decoder(
                 }
             }
         }
+        TypeExpression::Tuple(elements) => {
+            // Tuples are read positionally from an array of exactly their length
+            let mut steps = vec![tuple_length_check(elements.len(), ctx)?];
+            let mut values = Vec::new();
+            for (index, element) in elements.iter().enumerate() {
+                let inner = generate_type_decoder(element, None, ctx)?;
+                let binding = format!("element_{}", index);
+                steps.push(
+                    ctx.backend
+                        .then(&binding, &ctx.backend.element(index, &inner)),
+                );
+                values.push(binding);
+            }
+
+            Ok(format!(
+                "{{\n{}\n{}\n}}",
+                steps.join("\n"),
+                ctx.backend.success(&format!("#({})", values.join(", ")))
+            ))
+        }
+        TypeExpression::Var(name) if ctx.type_parameters.contains(name) => {
+            Ok(type_param_decoder_name(name))
+        }
         TypeExpression::Var(name) => Err(GlossError::GenerationError(format!(
             "Cannot derive decoder for generic field `{}`. Provide `decoder_with` override.",
             name
         ))),
-        TypeExpression::Tuple(_) | TypeExpression::Function { .. } | TypeExpression::Hole => Err(
+        TypeExpression::Function { .. } | TypeExpression::Hole => Err(
             GlossError::GenerationError(
                 "Cannot derive decoder for complex type expression. Provide `decoder_with` override.".to_string(),
             ),
@@ -440,18 +1447,15 @@ fn generate_single_constructor_encoder(
     arg_name: &str,
     mode: EncodingMode,
     field_naming: FieldNamingConvention,
-    type_tag_field: &str,
-    encoder_type: EncoderType,
-    backend: &dyn EncoderBackend,
-    registry: &TypeRegistry,
-    imports: &mut BTreeMap<String, ImportEntry>,
-    current_module_path: &str,
+    tagging: &Tagging,
     nesting: usize,
+    ctx: &mut EncoderContext,
 ) -> Result<String> {
     let indent = " ".repeat(nesting);
+    let backend = ctx.backend;
 
     if mode == EncodingMode::PlainString {
-        let tag = to_snake_case(&constructor.name);
+        let tag = variant_tag(constructor, tagging);
         return Ok(format!(
             r#"{}{}"#,
             indent,
@@ -464,177 +1468,698 @@ fn generate_single_constructor_encoder(
     }
 
     // Unpack fields
-    let field_labels: Vec<String> = constructor
-        .fields
-        .iter()
-        .map(|f| format!("{}:", f.label))
-        .collect();
-
-    let unpacking = if !field_labels.is_empty() {
+    let unpacking = if constructor.fields.iter().any(|f| !f.skip_encoding) {
         format!(
-            "{}let {}({}) = {}\n",
+            "{}let {} = {}\n",
             indent,
-            constructor.name,
-            field_labels.join(", "),
+            record_pattern(constructor),
             arg_name
         )
     } else {
         String::new()
     };
 
-    // Generate field encoders
-    let mut field_encoders: Vec<(String, String)> = Vec::new();
+    let mut leading_entries: Vec<(String, String)> = Vec::new();
 
     if mode == EncodingMode::ObjectWithTypeTag {
-        let tag = to_snake_case(&constructor.name);
-        field_encoders.push((
-            type_tag_field.to_string(),
+        let tag = variant_tag(constructor, tagging);
+        leading_entries.push((
+            tagging.tag_field.clone(),
             backend.encode_string_literal(&tag),
         ));
     }
 
-    for field in &constructor.fields {
-        let json_field_name = match &field.custom_name {
-            Some(name) => name.clone(),
-            None => convert_field_name(&field.label, field_naming),
-        };
-        let encoder = generate_type_encoder(
-            &field.label,
-            &field.type_expr,
-            field.encoder_with.as_deref(),
-            registry,
-            imports,
-            current_module_path,
-            backend,
-            encoder_type,
+    let object_expr = encode_constructor_fields(
+        constructor,
+        field_naming,
+        leading_entries,
+        &indent,
+        &indent,
+        ctx,
+    )?;
+
+    Ok(format!("{}{}", unpacking, object_expr))
+}
+
+fn generate_multi_constructor_encoder(
+    constructors: &[ConstructorInfo],
+    arg_name: &str,
+    mode: EncodingMode,
+    field_naming: FieldNamingConvention,
+    tagging: &Tagging,
+    fallback: Option<FallbackPayload>,
+    ctx: &mut EncoderContext,
+) -> Result<String> {
+    let mut cases = Vec::new();
+    // Fieldless fallback variants are written like any other variant
+    let fallback_payload = fallback.filter(|payload| *payload != FallbackPayload::Nothing);
+
+    for constructor in constructors {
+        if let Some(payload) = fallback_payload.filter(|_| constructor.fallback) {
+            let case = generate_fallback_encoder_case(constructor, payload, mode, tagging, ctx)?;
+            cases.push(case);
+            continue;
+        }
+
+        let pattern = record_pattern(constructor);
+
+        // Generate encoder without unpacking since we unpack in the pattern
+        let encoder = generate_constructor_encoder_body(
+            constructor,
+            mode,
+            field_naming,
+            tagging,
+            4,
+            ctx,
         )?;
-        field_encoders.push((json_field_name, encoder));
+
+        cases.push(format!("    {} -> {}", pattern, encoder.trim()));
+    }
+
+    let cases_str = cases.join("\n");
+
+    Ok(format!("  case {} {{\n{}\n  }}", arg_name, cases_str))
+}
+
+/// Case writing the fallback variant back as it was read: its tag alone, or the raw input
+fn generate_fallback_encoder_case(
+    constructor: &ConstructorInfo,
+    payload: FallbackPayload,
+    mode: EncodingMode,
+    tagging: &Tagging,
+    ctx: &mut EncoderContext,
+) -> Result<String> {
+    let backend = ctx.backend;
+    let field = &constructor.fields[0];
+    let (pattern, value) = if field.label.starts_with("_unlabeled") {
+        (format!("{}(value)", constructor.name), "value")
+    } else {
+        (
+            format!("{}({}:)", constructor.name, field.label),
+            field.label.as_str(),
+        )
+    };
+
+    let encoder = match payload {
+        FallbackPayload::Raw => {
+            if backend.json_module().is_none() {
+                return Err(GlossError::GenerationError(format!(
+                    "Fallback variant `{}` holds a `Dynamic` value, which only encoders built on the `gleam/json` API can encode",
+                    constructor.name
+                )));
+            }
+            ctx.uses_extra_value_helper = true;
+            format!("{}({})", ctx.extra_value_helper, value)
+        }
+        _ if mode == EncodingMode::PlainString => backend.encode_string(value),
+        _ => backend
+            .encode_object(
+                "    ",
+                &[(tagging.tag_field.clone(), backend.encode_string(value))],
+                "    ",
+            )
+            .trim()
+            .to_string(),
+    };
+
+    Ok(format!("    {} -> {}", pattern, encoder))
+}
+
+/// Pattern binding every encoded field of a constructor, e.g. `User(id:, name:, ..)`
+fn record_pattern(constructor: &ConstructorInfo) -> String {
+    if constructor.fields.is_empty() {
+        return constructor.name.clone();
+    }
+
+    let mut labels: Vec<String> = constructor
+        .fields
+        .iter()
+        .filter(|f| !f.skip_encoding)
+        .map(|f| format!("{}:", f.label))
+        .collect();
+    if labels.len() < constructor.fields.len() {
+        labels.push("..".to_string());
+    }
+
+    format!("{}({})", constructor.name, labels.join(", "))
+}
+
+fn generate_constructor_encoder_body(
+    constructor: &ConstructorInfo,
+    mode: EncodingMode,
+    field_naming: FieldNamingConvention,
+    tagging: &Tagging,
+    nesting: usize,
+    ctx: &mut EncoderContext,
+) -> Result<String> {
+    let backend = ctx.backend;
+
+    if mode == EncodingMode::PlainString {
+        let tag = variant_tag(constructor, tagging);
+        return Ok(backend.encode_string_literal(&tag));
     }
 
-    let object_expr = backend.encode_object(&indent, &field_encoders, &indent);
+    let indent = " ".repeat(nesting);
+    let inner_indent = " ".repeat(nesting + 4);
+    let tag = variant_tag(constructor, tagging);
+
+    match mode {
+        EncodingMode::AdjacentlyTagged => {
+            let mut entries = vec![(tagging.tag_field.clone(), backend.encode_string_literal(&tag))];
+            if constructor.fields.iter().any(|f| !f.skip_encoding) {
+                let content = encode_constructor_fields(
+                    constructor,
+                    field_naming,
+                    Vec::new(),
+                    &inner_indent,
+                    &inner_indent,
+                    ctx,
+                )?;
+                entries.push((tagging.content_field.clone(), content.trim().to_string()));
+            }
+            Ok(backend.encode_object(&indent, &entries, &indent))
+        }
+        EncodingMode::ExternallyTagged => {
+            let content = encode_constructor_fields(
+                constructor,
+                field_naming,
+                Vec::new(),
+                &inner_indent,
+                &inner_indent,
+                ctx,
+            )?;
+            Ok(backend.encode_object(&indent, &[(tag, content.trim().to_string())], &indent))
+        }
+        _ => {
+            let mut leading_entries = Vec::new();
+            if mode == EncodingMode::ObjectWithTypeTag {
+                leading_entries.push((
+                    tagging.tag_field.clone(),
+                    backend.encode_string_literal(&tag),
+                ));
+            }
+            encode_constructor_fields(
+                constructor,
+                field_naming,
+                leading_entries,
+                &indent,
+                &indent,
+                ctx,
+            )
+        }
+    }
+}
+
+/// Encode the fields of a constructor as an object placed after `leading_entries` (e.g. the
+/// type tag). `Option` fields using `absent_encoding = "omit"` and `extra` dictionaries only
+/// produce their entries at runtime, in which case the object is assembled from entry lists.
+fn encode_constructor_fields(
+    constructor: &ConstructorInfo,
+    field_naming: FieldNamingConvention,
+    leading_entries: Vec<(String, String)>,
+    indent: &str,
+    closing_indent: &str,
+    ctx: &mut EncoderContext,
+) -> Result<String> {
+    let backend = ctx.backend;
+    let mut parts = ObjectParts {
+        entries: leading_entries
+            .into_iter()
+            .map(|(key, value)| (key, ObjectValue::Encoded(value)))
+            .collect(),
+        groups: Vec::new(),
+    };
+    add_field_entries(&constructor.fields, None, field_naming, &mut parts, ctx)?;
+
+    let ObjectParts {
+        entries,
+        mut groups,
+    } = parts;
+    if groups.is_empty() {
+        let entries = render_object_entries(backend, &entries);
+        return Ok(backend.encode_object(indent, &entries, closing_indent));
+    }
+
+    if !entries.is_empty() {
+        groups.push(EntryGroup::Always(entries));
+    }
+    let entry_lists = groups
+        .iter()
+        .map(|group| match group {
+            EntryGroup::Always(entries) => {
+                entry_list(backend, &render_object_entries(backend, entries))
+            }
+            EntryGroup::Runtime(list) => list.clone(),
+        })
+        .collect::<Vec<_>>();
+    let flatten = format!("{}.flatten", ensure_stdlib_import(ctx.imports, "gleam/list"));
+    backend
+        .encode_object_from_entry_lists(indent, &entry_lists, closing_indent, &flatten)
+        .ok_or_else(|| {
+            GlossError::GenerationError(format!(
+                "Encoder backend `{}` cannot build objects whose entries are only known at runtime, as needed by `{}` for `absent_encoding = \"omit\"` or `extra` fields.",
+                backend.name(),
+                constructor.name
+            ))
+        })
+}
+
+/// Object entries collected so far. `entries` are always written and follow the last group.
+struct ObjectParts {
+    entries: Vec<(String, ObjectValue)>,
+    groups: Vec<EntryGroup>,
+}
+
+impl ObjectParts {
+    /// Close the current run of entries before a list that is only known at runtime
+    fn push_runtime_entries(&mut self, list: String) {
+        if !self.entries.is_empty() {
+            let entries = std::mem::take(&mut self.entries);
+            self.groups.push(EntryGroup::Always(entries));
+        }
+        self.groups.push(EntryGroup::Runtime(list));
+    }
+
+    /// Add an entry under `path`, creating the objects along the way or reusing the ones
+    /// created by earlier fields sharing a prefix of the path
+    fn insert_path_entry(&mut self, path: &[String], value: String) {
+        let (key, rest) = path.split_first().expect("key path is never empty");
+        if rest.is_empty() {
+            self.entries
+                .push((key.clone(), ObjectValue::Encoded(value)));
+            return;
+        }
+
+        let existing = self
+            .groups
+            .iter_mut()
+            .filter_map(|group| match group {
+                EntryGroup::Always(entries) => Some(entries),
+                EntryGroup::Runtime(_) => None,
+            })
+            .chain(std::iter::once(&mut self.entries))
+            .flat_map(|entries| entries.iter_mut())
+            .find_map(|(existing_key, object)| match object {
+                ObjectValue::Nested(entries) if existing_key == key => Some(entries),
+                _ => None,
+            });
+        match existing {
+            Some(entries) => insert_nested_entry(entries, rest, value),
+            None => {
+                let mut entries = Vec::new();
+                insert_nested_entry(&mut entries, rest, value);
+                self.entries
+                    .push((key.clone(), ObjectValue::Nested(entries)));
+            }
+        }
+    }
+}
+
+/// Run of entries that are always written, or a Gleam expression evaluating to a list of
+/// entries decided at runtime
+enum EntryGroup {
+    Always(Vec<(String, ObjectValue)>),
+    Runtime(String),
+}
+
+/// Value of an object entry: an encoded field or an object built from `path` fields
+enum ObjectValue {
+    Encoded(String),
+    Nested(Vec<(String, ObjectValue)>),
+}
+
+fn insert_nested_entry(entries: &mut Vec<(String, ObjectValue)>, path: &[String], value: String) {
+    let (key, rest) = path.split_first().expect("key path is never empty");
+    if rest.is_empty() {
+        entries.push((key.clone(), ObjectValue::Encoded(value)));
+        return;
+    }
+
+    let existing = entries
+        .iter_mut()
+        .find_map(|(existing_key, object)| match object {
+            ObjectValue::Nested(nested) if existing_key == key => Some(nested),
+            _ => None,
+        });
+    match existing {
+        Some(nested) => insert_nested_entry(nested, rest, value),
+        None => {
+            let mut nested = Vec::new();
+            insert_nested_entry(&mut nested, rest, value);
+            entries.push((key.clone(), ObjectValue::Nested(nested)));
+        }
+    }
+}
+
+fn render_object_entries(
+    backend: &dyn EncoderBackend,
+    entries: &[(String, ObjectValue)],
+) -> Vec<(String, String)> {
+    entries
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                ObjectValue::Encoded(value) => value.clone(),
+                ObjectValue::Nested(entries) => {
+                    let entries = render_object_entries(backend, entries);
+                    backend.encode_object("", &entries, "").trim().to_string()
+                }
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+/// Add the entries of `fields`, read from `record` when they belong to a flattened record
+/// and from the variables bound by the record pattern otherwise.
+fn add_field_entries<'a>(
+    fields: &[FieldInfo],
+    record: Option<&str>,
+    field_naming: FieldNamingConvention,
+    parts: &mut ObjectParts,
+    ctx: &mut EncoderContext<'a>,
+) -> Result<()> {
+    let backend = ctx.backend;
+
+    for field in fields.iter().filter(|f| !f.skip_encoding) {
+        let value_expr = match record {
+            Some(record) => format!("{}.{}", record, field.label),
+            None => field.label.clone(),
+        };
+
+        if field.extra {
+            // Captured keys are appended after the known fields
+            let dict_alias = ensure_stdlib_import(ctx.imports, "gleam/dict");
+            let captured = format!("{}.to_list({})", dict_alias, value_expr);
+            let values = extra_values(field)?;
+            let captured_entries = match values {
+                ExtraValues::Json => {
+                    if backend.json_module().map(|(module, _)| module) != Some("gleam/json") {
+                        return Err(GlossError::GenerationError(format!(
+                            "Field `{}` holds `json.Json` values, which only encoders built on `gleam/json` can write",
+                            field.label
+                        )));
+                    }
+                    captured
+                }
+                ExtraValues::Dynamic => {
+                    if backend.json_module().is_none() {
+                        return Err(GlossError::GenerationError(format!(
+                            "Field `{}` holds `Dynamic` values, which only encoders built on the `gleam/json` API can encode",
+                            field.label
+                        )));
+                    }
+                    ctx.uses_extra_value_helper = true;
+                    let list_alias = ensure_stdlib_import(ctx.imports, "gleam/list");
+                    format!(
+                        "{}.map({}, fn(entry) {{ #(entry.0, {}(entry.1)) }})",
+                        list_alias, captured, ctx.extra_value_helper
+                    )
+                }
+            };
+            parts.push_runtime_entries(captured_entries);
+            continue;
+        }
+
+        if field.flatten {
+            let type_lookup: &'a TypeLookup = ctx.type_lookup;
+            let nested = flattened_type(field, ctx.current_module_path, ctx.registry, type_lookup)?;
+            let nested_naming = nested
+                .field_naming_strategy
+                .unwrap_or(ctx.config.field_naming_strategy);
+
+            // Field types of the nested record are relative to its own module, while the
+            // encoder is still written into `output_module_path`
+            let outer_module_path = ctx.current_module_path;
+            ctx.current_module_path = &nested.module_path;
+            let result = add_field_entries(
+                &nested.constructors[0].fields,
+                Some(&value_expr),
+                nested_naming,
+                parts,
+                ctx,
+            );
+            ctx.current_module_path = outer_module_path;
+            result?;
+            continue;
+        }
+
+        let key_path = field_key_path(field, field_naming);
+
+        // Values written under a nested path are always present, `None` being encoded as null
+        let omitted = match key_path.as_slice() {
+            [_] => omitted_option_argument(field, ctx.config.absent_encoding),
+            _ => None,
+        };
+        match omitted {
+            Some(inner_type) => {
+                let option_alias = ensure_stdlib_import(ctx.imports, "gleam/option");
+                let binding = value_expr.replace('.', "_");
+                let encoder = generate_type_encoder(&binding, inner_type, None, ctx)?;
+                parts.push_runtime_entries(format!(
+                    "case {value} {{ {option}.Some({binding}) -> [{entry}] {option}.None -> [] }}",
+                    value = value_expr,
+                    binding = binding,
+                    option = option_alias,
+                    entry = backend.encode_entry(&key_path[0], &encoder)
+                ));
+            }
+            None => {
+                let encoder = generate_type_encoder(
+                    &value_expr,
+                    &field.type_expr,
+                    field.encoder_with.as_deref(),
+                    ctx,
+                )?;
+                parts.insert_path_entry(&key_path, encoder);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Annotated record whose fields are merged into the parent object by a `flatten` field
+fn flattened_type<'l>(
+    field: &FieldInfo,
+    current_module_path: &str,
+    registry: &TypeRegistry,
+    type_lookup: &'l TypeLookup,
+) -> Result<&'l CustomTypeInfo> {
+    let not_flattenable = |reason: &str| {
+        GlossError::GenerationError(format!(
+            "Field `{}` cannot be flattened: {}",
+            field.label, reason
+        ))
+    };
+
+    let (module, name) = match &field.type_expr {
+        TypeExpression::Constructor {
+            module,
+            name,
+            arguments,
+        } if arguments.is_empty() => (module, name),
+        _ => return Err(not_flattenable("only records without type arguments can be flattened")),
+    };
+
+    let nested = find_type_entry(registry, module.as_deref(), name, current_module_path)
+        .and_then(|entry| type_lookup.get(&(entry.module_path.clone(), name.clone())))
+        .ok_or_else(|| not_flattenable(&format!("`{}` is not an annotated type", name)))?;
+
+    if !nested.parameters.is_empty() {
+        return Err(not_flattenable(
+            "only records without type arguments can be flattened",
+        ));
+    }
+    if nested.constructors.len() != 1 || nested.int_enum {
+        return Err(not_flattenable(&format!(
+            "`{}` must have exactly one constructor",
+            name
+        )));
+    }
+    if let Some(unlabeled) = nested.constructors[0]
+        .fields
+        .iter()
+        .find(|f| f.label.starts_with("_unlabeled"))
+    {
+        return Err(not_flattenable(&format!(
+            "`{}` has an unlabeled field of type `{}`",
+            name, unlabeled.type_
+        )));
+    }
 
-    Ok(format!("{}{}", unpacking, object_expr))
+    Ok(nested)
 }
 
-fn generate_multi_constructor_encoder(
-    constructors: &[ConstructorInfo],
-    arg_name: &str,
+/// Reject objects in which two fields, possibly brought in by flattened records, or a field
+/// and the type tag share a key
+fn ensure_unique_object_keys(
+    type_info: &CustomTypeInfo,
     mode: EncodingMode,
+    tagging: &Tagging,
     field_naming: FieldNamingConvention,
-    type_tag_field: &str,
-    encoder_type: EncoderType,
-    backend: &dyn EncoderBackend,
+    config: &Config,
     registry: &TypeRegistry,
-    imports: &mut BTreeMap<String, ImportEntry>,
-    current_module_path: &str,
-) -> Result<String> {
-    let mut cases = Vec::new();
-
-    for constructor in constructors {
-        let constructor_name = &constructor.name;
-
-        let field_labels: Vec<String> = constructor
-            .fields
-            .iter()
-            .map(|f| format!("{}:", f.label))
-            .collect();
+    type_lookup: &TypeLookup,
+) -> Result<()> {
+    if type_info.int_enum || mode == EncodingMode::PlainString {
+        return Ok(());
+    }
 
-        let pattern = if field_labels.is_empty() {
-            constructor_name.clone()
-        } else {
-            format!("{}({})", constructor_name, field_labels.join(", "))
-        };
+    // Fields of the fallback variant hold the tag or the raw input rather than keys
+    for constructor in type_info.constructors.iter().filter(|c| !c.fallback) {
+        let mut keys: Vec<(Vec<String>, String)> = Vec::new();
+        if mode == EncodingMode::ObjectWithTypeTag {
+            keys.push((vec![tagging.tag_field.clone()], "the type tag".to_string()));
+        }
 
-        // Generate encoder without unpacking since we unpack in the pattern
-        let encoder = generate_constructor_encoder_body(
-            constructor,
-            mode,
+        let mut flattening = vec![type_info.name.clone()];
+        collect_object_keys(
+            &constructor.fields,
             field_naming,
-            type_tag_field,
-            encoder_type,
-            backend,
+            &type_info.module_path,
+            &type_info.name,
+            config,
             registry,
-            imports,
-            current_module_path,
-            4,
+            type_lookup,
+            &mut keys,
+            &mut flattening,
         )?;
-
-        cases.push(format!("    {} -> {}", pattern, encoder.trim()));
     }
 
-    let cases_str = cases.join("\n");
-
-    Ok(format!("  case {} {{\n{}\n  }}", arg_name, cases_str))
+    Ok(())
 }
 
-fn generate_constructor_encoder_body(
-    constructor: &ConstructorInfo,
-    mode: EncodingMode,
+#[allow(clippy::too_many_arguments)]
+fn collect_object_keys(
+    fields: &[FieldInfo],
     field_naming: FieldNamingConvention,
-    type_tag_field: &str,
-    encoder_type: EncoderType,
-    backend: &dyn EncoderBackend,
+    module_path: &str,
+    owner: &str,
+    config: &Config,
     registry: &TypeRegistry,
-    imports: &mut BTreeMap<String, ImportEntry>,
-    current_module_path: &str,
-    nesting: usize,
-) -> Result<String> {
-    if mode == EncodingMode::PlainString {
-        let tag = to_snake_case(&constructor.name);
-        return Ok(backend.encode_string_literal(&tag));
+    type_lookup: &TypeLookup,
+    keys: &mut Vec<(Vec<String>, String)>,
+    flattening: &mut Vec<String>,
+) -> Result<()> {
+    for field in fields {
+        if (field.skip_encoding && field.skip_decoding) || field.extra {
+            continue;
+        }
+
+        if field.flatten {
+            let nested = flattened_type(field, module_path, registry, type_lookup)?;
+            if flattening.contains(&nested.name) {
+                return Err(GlossError::GenerationError(format!(
+                    "Field `{}` of `{}` flattens `{}` into itself",
+                    field.label, owner, nested.name
+                )));
+            }
+
+            flattening.push(nested.name.clone());
+            collect_object_keys(
+                &nested.constructors[0].fields,
+                nested
+                    .field_naming_strategy
+                    .unwrap_or(config.field_naming_strategy),
+                &nested.module_path,
+                &nested.name,
+                config,
+                registry,
+                type_lookup,
+                keys,
+                flattening,
+            )?;
+            flattening.pop();
+            continue;
+        }
+
+        // Keys clash when they are equal or when one is a parent object of the other
+        let key_path = field_key_path(field, field_naming);
+        let description = format!("field `{}` of `{}`", field.label, owner);
+        if let Some((previous_path, previous)) = keys
+            .iter()
+            .find(|(path, _)| path.iter().zip(&key_path).all(|(a, b)| a == b))
+        {
+            let key = if previous_path.len() < key_path.len() {
+                previous_path
+            } else {
+                &key_path
+            };
+            return Err(GlossError::GenerationError(format!(
+                "Key \"{}\" is used by both {} and {}",
+                key.join("."),
+                previous,
+                description
+            )));
+        }
+        keys.push((key_path, description));
     }
 
-    let indent = " ".repeat(nesting);
+    Ok(())
+}
 
-    if constructor.fields.is_empty() {
-        return Ok(backend.encode_empty_object(&indent));
+/// Location of a field in the object: its `path`, or else its (renamed) key
+fn field_key_path(field: &FieldInfo, field_naming: FieldNamingConvention) -> Vec<String> {
+    if !field.path.is_empty() {
+        return field.path.clone();
     }
 
-    let mut field_encoders: Vec<(String, String)> = Vec::new();
+    vec![match &field.custom_name {
+        Some(name) => name.clone(),
+        None => convert_name(&field.label, field_naming),
+    }]
+}
 
-    if mode == EncodingMode::ObjectWithTypeTag {
-        let tag = to_snake_case(&constructor.name);
-        field_encoders.push((
-            type_tag_field.to_string(),
-            backend.encode_string_literal(&tag),
-        ));
+/// Inner type of an `Option` field whose `None` leaves the key out of the object
+fn omitted_option_argument(
+    field: &FieldInfo,
+    project_default: Option<AbsentEncoding>,
+) -> Option<&TypeExpression> {
+    if field.encoder_with.is_some()
+        || field
+            .absent_encoding
+            .or(project_default)
+            .unwrap_or_default()
+            != AbsentEncoding::Omit
+    {
+        return None;
     }
 
-    for field in &constructor.fields {
-        let json_field_name = match &field.custom_name {
-            Some(name) => name.clone(),
-            None => convert_field_name(&field.label, field_naming),
-        };
-        let encoder = generate_type_encoder(
-            &field.label,
-            &field.type_expr,
-            field.encoder_with.as_deref(),
-            registry,
-            imports,
-            current_module_path,
-            backend,
-            encoder_type,
-        )?;
-        field_encoders.push((json_field_name, encoder));
+    match &field.type_expr {
+        TypeExpression::Constructor { arguments, .. } if field.type_expr.is_standard_option() => {
+            arguments.first()
+        }
+        _ => None,
     }
+}
 
-    Ok(backend.encode_object(&indent, &field_encoders, &indent))
+fn entry_list(backend: &dyn EncoderBackend, entries: &[(String, String)]) -> String {
+    let entries = entries
+        .iter()
+        .map(|(key, value)| backend.encode_entry(key, value))
+        .collect::<Vec<_>>();
+    format!("[{}]", entries.join(", "))
 }
 
 fn generate_type_encoder(
     var_name: &str,
     type_expr: &TypeExpression,
     override_fn: Option<&str>,
-    registry: &TypeRegistry,
-    imports: &mut BTreeMap<String, ImportEntry>,
-    current_module_path: &str,
-    backend: &dyn EncoderBackend,
-    encoder_type: EncoderType,
+    ctx: &mut EncoderContext,
 ) -> Result<String> {
     if let Some(override_path) = override_fn {
-        return resolve_encoder_override(override_path, var_name, imports, current_module_path);
+        return resolve_encoder_override(
+            override_path,
+            var_name,
+            ctx.imports,
+            ctx.current_module_path,
+            ctx.output_module_path,
+        );
     }
 
+    let backend = ctx.backend;
+
     match type_expr {
         TypeExpression::Constructor {
             module,
@@ -647,12 +2172,21 @@ fn generate_type_encoder(
                 && module.as_deref() == Some("gleam/option")
                 && !arguments.is_empty()
             {
-                // Placeholder inner encoder until optional helpers support richer expressions
-                return Ok(backend.encode_nullable(var_name, "_"));
+                let inner = generate_encoder_function(&arguments[0], ctx)?;
+                return Ok(backend.encode_nullable(var_name, &inner));
             }
 
             if name_str == "List" && !arguments.is_empty() {
-                return Ok(backend.encode_array(var_name, "_"));
+                let inner = generate_encoder_function(&arguments[0], ctx)?;
+                return Ok(backend.encode_array(var_name, &inner));
+            }
+
+            if is_standard_dict(module.as_deref(), name_str, arguments) {
+                ensure_string_dict_key(&arguments[0])?;
+                let inner = generate_encoder_function(&arguments[1], ctx)?;
+                return backend
+                    .encode_dict(var_name, &inner)
+                    .ok_or_else(|| unsupported_by_backend(backend, "dictionaries"));
             }
 
             match name_str {
@@ -660,20 +2194,42 @@ fn generate_type_encoder(
                 "Int" => Ok(backend.encode_int(var_name)),
                 "Float" => Ok(backend.encode_float(var_name)),
                 "Bool" => Ok(backend.encode_bool(var_name)),
+                "Nil" => backend
+                    .encode_nil()
+                    .ok_or_else(|| unsupported_by_backend(backend, "`Nil`")),
+                "BitArray" => {
+                    let text_expr = if backend.bit_array_text_form() {
+                        let bit_array = ensure_stdlib_import(ctx.imports, "gleam/bit_array");
+                        match ctx.config.bit_array_encoding.unwrap_or_default() {
+                            BitArrayEncoding::Base64 => {
+                                format!("{}.base64_encode({}, True)", bit_array, var_name)
+                            }
+                            BitArrayEncoding::Hex => {
+                                format!("{}.base16_encode({})", bit_array, var_name)
+                            }
+                        }
+                    } else {
+                        String::new()
+                    };
+                    backend
+                        .encode_bit_array(var_name, &text_expr)
+                        .ok_or_else(|| unsupported_by_backend(backend, "bit arrays"))
+                }
                 _ => {
                     if let Some(entry) = find_type_entry(
-                        registry,
+                        ctx.registry,
                         module.as_deref(),
                         name,
-                        current_module_path,
+                        ctx.current_module_path,
                     ) {
-                        let backend_id = encoder_type.identifier();
+                        let backend_id = ctx.encoder_type.identifier();
                         if !entry.encoder_fn_names.contains_key(backend_id) {
                             return Err(GlossError::GenerationError(format!(
                                 "Encoder requested for type `{}` with backend `{}` but gloss is not generating one. Provide `encoder_with` override.",
                                 name, backend_id
                             )));
                         }
+                        ensure_type_argument_count(name, entry.parameters.len(), arguments.len())?;
 
                         let encoder_name = entry
                             .encoder_fn_names
@@ -683,14 +2239,20 @@ fn generate_type_encoder(
                                 format!("{}_to_{}", to_snake_case(name), backend_id)
                             });
 
-                        if entry.module_path == current_module_path {
-                            Ok(format!("{}({})", encoder_name, var_name))
+                        // Generic types receive an encoder function for each type argument
+                        let mut call_arguments = vec![var_name.to_string()];
+                        for argument in arguments {
+                            call_arguments.push(generate_encoder_function(argument, ctx)?);
+                        }
+
+                        if entry.module_path == ctx.output_module_path {
+                            Ok(format!("{}({})", encoder_name, call_arguments.join(", ")))
                         } else {
-                            let alias = ensure_import(imports, &entry.module_path);
+                            let alias = ensure_import(ctx.imports, &entry.module_path);
                             Ok(format!(
                                 "{}.{encoder_name}({})",
                                 alias,
-                                var_name,
+                                call_arguments.join(", "),
                                 encoder_name = encoder_name
                             ))
                         }
@@ -703,11 +2265,29 @@ fn generate_type_encoder(
                 }
             }
         }
+        TypeExpression::Tuple(elements) => {
+            // Tuples are written as fixed-length arrays
+            let mut encoded = Vec::new();
+            for (index, element) in elements.iter().enumerate() {
+                encoded.push(generate_type_encoder(
+                    &format!("{}.{}", var_name, index),
+                    element,
+                    None,
+                    ctx,
+                )?);
+            }
+            backend
+                .encode_tuple(&encoded)
+                .ok_or_else(|| unsupported_by_backend(backend, "tuples"))
+        }
+        TypeExpression::Var(name) if ctx.type_parameters.contains(name) => {
+            Ok(format!("{}({})", type_param_encoder_name(name), var_name))
+        }
         TypeExpression::Var(name) => Err(GlossError::GenerationError(format!(
             "Cannot derive encoder for generic field `{}`. Provide `encoder_with` override.",
             name
         ))),
-        TypeExpression::Tuple(_) | TypeExpression::Function { .. } | TypeExpression::Hole => Err(
+        TypeExpression::Function { .. } | TypeExpression::Hole => Err(
             GlossError::GenerationError(
                 "Cannot derive encoder for complex type expression. Provide `encoder_with` override.".to_string(),
             ),
@@ -715,6 +2295,102 @@ fn generate_type_encoder(
     }
 }
 
+fn ensure_type_argument_count(type_name: &str, expected: usize, found: usize) -> Result<()> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(GlossError::GenerationError(format!(
+            "Type `{}` expects {} type argument(s) but {} were given",
+            type_name, expected, found
+        )))
+    }
+}
+
+fn is_standard_dict(module: Option<&str>, name: &str, arguments: &[TypeExpression]) -> bool {
+    name == "Dict" && module == Some("gleam/dict") && arguments.len() == 2
+}
+
+/// JSON objects only have string keys, so dictionaries must be keyed by `String`
+fn ensure_string_dict_key(key_type: &TypeExpression) -> Result<()> {
+    match key_type {
+        TypeExpression::Constructor {
+            module: None,
+            name,
+            arguments,
+        } if name == "String" && arguments.is_empty() => Ok(()),
+        other => Err(GlossError::GenerationError(format!(
+            "Dictionary keys must be `String` to map onto object keys, found `{}`. Provide `decoder_with`/`encoder_with` overrides for other key types.",
+            describe_type_expr(other)
+        ))),
+    }
+}
+
+fn unsupported_by_backend(backend: &dyn EncoderBackend, construct: &str) -> GlossError {
+    GlossError::GenerationError(format!(
+        "Encoder backend `{}` does not support {}. Provide an `encoder_with` override.",
+        backend.name(),
+        construct
+    ))
+}
+
+/// Render a type expression using Gleam syntax, for error messages
+fn describe_type_expr(type_expr: &TypeExpression) -> String {
+    match type_expr {
+        TypeExpression::Constructor {
+            module,
+            name,
+            arguments,
+        } => {
+            let qualified = match module {
+                Some(module) => format!("{}.{}", module, name),
+                None => name.clone(),
+            };
+            if arguments.is_empty() {
+                qualified
+            } else {
+                let args = arguments
+                    .iter()
+                    .map(describe_type_expr)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{}({})", qualified, args)
+            }
+        }
+        TypeExpression::Tuple(elements) => format!(
+            "#({})",
+            elements
+                .iter()
+                .map(describe_type_expr)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        TypeExpression::Function {
+            arguments,
+            return_type,
+        } => format!(
+            "fn({}) -> {}",
+            arguments
+                .iter()
+                .map(describe_type_expr)
+                .collect::<Vec<_>>()
+                .join(", "),
+            describe_type_expr(return_type)
+        ),
+        TypeExpression::Var(name) => name.clone(),
+        TypeExpression::Hole => "_".to_string(),
+    }
+}
+
+/// Name of the parameter used by generated inner encoder functions
+const ENCODER_FN_PARAM: &str = "value";
+
+/// Build a function value encoding a single `type_expr`, used as the inner encoder
+/// of nullable and array helpers.
+fn generate_encoder_function(type_expr: &TypeExpression, ctx: &mut EncoderContext) -> Result<String> {
+    let body = generate_type_encoder(ENCODER_FN_PARAM, type_expr, None, ctx)?;
+    Ok(ctx.backend.encoder_function(ENCODER_FN_PARAM, &body))
+}
+
 #[derive(Debug)]
 struct FunctionReference {
     module_path: Option<String>,
@@ -725,20 +2401,127 @@ fn resolve_decoder_override(
     value: &str,
     imports: &mut BTreeMap<String, ImportEntry>,
     current_module_path: &str,
+    output_module_path: &str,
 ) -> Result<String> {
-    let reference = parse_function_reference(value)?;
-    let path = render_function_path(&reference, imports, current_module_path);
+    let mut reference = parse_function_reference(value)?;
+    // Unqualified functions live next to the type declaring the override
+    reference
+        .module_path
+        .get_or_insert_with(|| current_module_path.to_string());
+    let path = render_function_path(&reference, imports, output_module_path);
     Ok(format!("{}()", path))
 }
 
+/// Value of a field that is not read from the input: its `default`, or else a zero value
+fn skipped_field_value(field: &FieldInfo, ctx: &mut DecoderContext) -> Result<String> {
+    if let Some(default) = &field.default {
+        return resolve_field_default(default, ctx);
+    }
+
+    default_value_for_type_expr(
+        &field.type_expr,
+        ctx.current_module_path,
+        ctx.output_module_path,
+        ctx.type_lookup,
+        ctx.imports,
+        &mut HashSet::new(),
+        &mut ctx.uses_option_helpers,
+    )
+    .ok_or_else(|| {
+        GlossError::GenerationError(format!(
+            "Field `{}` is skipped during decoding but has no default value. Add `default = ...` or `default_fn = \"module.function\"`.",
+            field.label
+        ))
+    })
+}
+
+/// Render a field default so that it is valid inside the generated module
+fn resolve_field_default(default: &FieldDefault, ctx: &mut DecoderContext) -> Result<String> {
+    match default {
+        FieldDefault::Function(value) => resolve_decoder_override(
+            value,
+            ctx.imports,
+            ctx.current_module_path,
+            ctx.output_module_path,
+        ),
+        FieldDefault::Expression(expression) => Ok(requalify_expression(expression, ctx)),
+    }
+}
+
+/// Rewrite `alias.name` references written against the declaring module's imports
+/// to the aliases used by the generated module, importing modules as needed.
+fn requalify_expression(expression: &str, ctx: &mut DecoderContext) -> String {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut output = String::with_capacity(expression.len());
+    let mut index = 0;
+    let mut in_string = false;
+
+    while index < chars.len() {
+        let ch = chars[index];
+
+        if in_string {
+            output.push(ch);
+            if ch == '\\' && index + 1 < chars.len() {
+                output.push(chars[index + 1]);
+                index += 1;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            index += 1;
+            continue;
+        }
+
+        let starts_identifier = (ch.is_ascii_lowercase() || ch == '_')
+            && (index == 0 || (!is_identifier_char(chars[index - 1]) && chars[index - 1] != '.'));
+        if !starts_identifier {
+            if ch == '"' {
+                in_string = true;
+            }
+            output.push(ch);
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        while index < chars.len() && is_identifier_char(chars[index]) {
+            index += 1;
+        }
+        let identifier: String = chars[start..index].iter().collect();
+
+        let module_path = if chars.get(index) == Some(&'.') {
+            ctx.module_imports.get(&identifier)
+        } else {
+            None
+        };
+        match module_path {
+            Some(module_path) if module_path.starts_with("gleam/") => {
+                output.push_str(&ensure_stdlib_import(ctx.imports, module_path));
+            }
+            Some(module_path) => output.push_str(&ensure_import(ctx.imports, module_path)),
+            None => output.push_str(&identifier),
+        }
+    }
+
+    output
+}
+
+fn is_identifier_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric() || ch == '_'
+}
+
 fn resolve_encoder_override(
     value: &str,
     argument: &str,
     imports: &mut BTreeMap<String, ImportEntry>,
     current_module_path: &str,
+    output_module_path: &str,
 ) -> Result<String> {
-    let reference = parse_function_reference(value)?;
-    let path = render_function_path(&reference, imports, current_module_path);
+    let mut reference = parse_function_reference(value)?;
+    // Unqualified functions live next to the type declaring the override
+    reference
+        .module_path
+        .get_or_insert_with(|| current_module_path.to_string());
+    let path = render_function_path(&reference, imports, output_module_path);
     Ok(format!("{}({})", path, argument))
 }
 
@@ -794,6 +2577,31 @@ fn ensure_import(imports: &mut BTreeMap<String, ImportEntry>, module_path: &str)
     ensure_import_entry(imports, module_path).alias.clone()
 }
 
+/// Import a standard library module under its conventional alias (`gleam/dict` as `dict`),
+/// falling back to a path-derived alias when that name is already taken.
+fn ensure_stdlib_import(imports: &mut BTreeMap<String, ImportEntry>, module_path: &str) -> String {
+    if let Some(entry) = imports.get(module_path) {
+        return entry.alias.clone();
+    }
+
+    let short_alias = module_path
+        .rsplit('/')
+        .next()
+        .unwrap_or(module_path)
+        .to_string();
+    let alias = if imports.values().any(|entry| entry.alias == short_alias) {
+        module_alias(module_path)
+    } else {
+        short_alias
+    };
+
+    imports.insert(
+        module_path.to_string(),
+        ImportEntry::new(module_path, alias.clone()),
+    );
+    alias
+}
+
 fn ensure_import_entry<'a>(
     imports: &'a mut BTreeMap<String, ImportEntry>,
     module_path: &str,
@@ -809,10 +2617,34 @@ fn ensure_import_entry<'a>(
     }
 }
 
-fn format_expected_variants(constructors: &[ConstructorInfo]) -> String {
+/// Tag identifying a variant on the wire
+fn variant_tag(constructor: &ConstructorInfo, tagging: &Tagging) -> String {
+    constructor
+        .tag
+        .clone()
+        .unwrap_or_else(|| convert_name(&constructor.name, tagging.variant_naming))
+}
+
+fn ensure_unique_variant_tags(type_info: &CustomTypeInfo, tagging: &Tagging) -> Result<()> {
+    let mut seen: BTreeMap<String, &str> = BTreeMap::new();
+    // Skipped variants are still encoded, so their tags must stay distinct too
+    for constructor in &type_info.constructors {
+        let tag = variant_tag(constructor, tagging);
+        if let Some(previous) = seen.insert(tag.clone(), &constructor.name) {
+            return Err(GlossError::GenerationError(format!(
+                "Variants `{}` and `{}` of type `{}` both use the tag \"{}\"",
+                previous, constructor.name, type_info.name, tag
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn format_expected_variants(constructors: &[ConstructorInfo], tagging: &Tagging) -> String {
     let mut tags: Vec<String> = constructors
         .iter()
-        .map(|constructor| to_snake_case(&constructor.name))
+        .filter(|constructor| !constructor.skip && !constructor.deprecated)
+        .map(|constructor| variant_tag(constructor, tagging))
         .collect();
     tags.sort();
     tags.dedup();
@@ -843,6 +2675,8 @@ fn default_value_for_type(
     .unwrap_or_else(|| panic_default_message(&format!("{}", type_info.name)))
 }
 
+/// Build a zero value for an annotated type, trying each constructor in turn so
+/// that recursive types pick a variant which does not refer back to themselves.
 fn build_default_for_custom_type(
     target_module: &str,
     type_name: &str,
@@ -853,23 +2687,34 @@ fn build_default_for_custom_type(
     uses_option_helpers: &mut bool,
 ) -> Option<String> {
     let key = (target_module.to_string(), type_name.to_string());
+    let type_info = type_lookup.get(&key)?;
     if !visited.insert(key.clone()) {
         return None;
     }
 
-    let type_info = type_lookup.get(&key)?;
-    let constructor = type_info.constructors.first()?;
-    let expression = build_constructor_expression(
-        constructor,
-        target_module,
-        context_module,
-        type_lookup,
-        imports,
-        visited,
-        uses_option_helpers,
-    );
+    let mut expression = None;
+    for constructor in &type_info.constructors {
+        // Imports are only kept for the constructor that is eventually used
+        let mut attempt_imports = imports.clone();
+        let mut attempt_uses_option_helpers = *uses_option_helpers;
+        if let Some(value) = build_constructor_expression(
+            constructor,
+            target_module,
+            context_module,
+            type_lookup,
+            &mut attempt_imports,
+            visited,
+            &mut attempt_uses_option_helpers,
+        ) {
+            *imports = attempt_imports;
+            *uses_option_helpers = attempt_uses_option_helpers;
+            expression = Some(value);
+            break;
+        }
+    }
+
     visited.remove(&key);
-    Some(expression)
+    expression
 }
 
 fn build_constructor_expression(
@@ -880,7 +2725,7 @@ fn build_constructor_expression(
     imports: &mut BTreeMap<String, ImportEntry>,
     visited: &mut HashSet<(String, String)>,
     uses_option_helpers: &mut bool,
-) -> String {
+) -> Option<String> {
     let prefix = if constructor_module == context_module {
         constructor.name.clone()
     } else {
@@ -889,26 +2734,23 @@ fn build_constructor_expression(
     };
 
     if constructor.fields.is_empty() {
-        prefix
-    } else {
-        let field_values: Vec<String> = constructor
-            .fields
-            .iter()
-            .map(|field| {
-                let value = default_value_for_type_expr(
-                    &field.type_expr,
-                    constructor_module,
-                    context_module,
-                    type_lookup,
-                    imports,
-                    visited,
-                    uses_option_helpers,
-                );
-                constructor_argument(field, value)
-            })
-            .collect();
-        format!("{}({})", prefix, field_values.join(", "))
+        return Some(prefix);
+    }
+
+    let mut field_values = Vec::new();
+    for field in &constructor.fields {
+        let value = default_value_for_type_expr(
+            &field.type_expr,
+            constructor_module,
+            context_module,
+            type_lookup,
+            imports,
+            visited,
+            uses_option_helpers,
+        )?;
+        field_values.push(constructor_argument(field, value));
     }
+    Some(format!("{}({})", prefix, field_values.join(", ")))
 }
 
 fn default_value_for_type_expr(
@@ -919,7 +2761,7 @@ fn default_value_for_type_expr(
     imports: &mut BTreeMap<String, ImportEntry>,
     visited: &mut HashSet<(String, String)>,
     uses_option_helpers: &mut bool,
-) -> String {
+) -> Option<String> {
     match type_expr {
         TypeExpression::Constructor {
             module,
@@ -932,55 +2774,46 @@ fn default_value_for_type_expr(
                 .unwrap_or_else(|| current_module.to_string());
 
             match name.as_str() {
-                "String" => "\"\"".to_string(),
-                "Int" => "0".to_string(),
-                "Float" => "0.0".to_string(),
-                "Bool" => "False".to_string(),
-                "List" if arguments.len() == 1 => "[]".to_string(),
+                "String" => Some("\"\"".to_string()),
+                "Int" => Some("0".to_string()),
+                "Float" => Some("0.0".to_string()),
+                "Bool" => Some("False".to_string()),
+                "List" if arguments.len() == 1 => Some("[]".to_string()),
+                "Dict" if is_standard_dict(module.as_deref(), name, arguments) => Some(format!(
+                    "{}.new()",
+                    ensure_stdlib_import(imports, "gleam/dict")
+                )),
                 "Option" if arguments.len() == 1 && module.as_deref() == Some("gleam/option") => {
                     *uses_option_helpers = true;
-                    "option.None".to_string()
-                }
-                _ => {
-                    let display_name = if module_path == context_module {
-                        name.clone()
-                    } else {
-                        format!("{}.{name}", module_path.replace('/', "."), name = name)
-                    };
-
-                    build_default_for_custom_type(
-                        &module_path,
-                        name,
-                        context_module,
-                        type_lookup,
-                        imports,
-                        visited,
-                        uses_option_helpers,
-                    )
-                    .unwrap_or_else(|| panic_default_message(&display_name))
+                    Some("option.None".to_string())
                 }
+                _ => build_default_for_custom_type(
+                    &module_path,
+                    name,
+                    context_module,
+                    type_lookup,
+                    imports,
+                    visited,
+                    uses_option_helpers,
+                ),
             }
         }
         TypeExpression::Tuple(elements) => {
-            let values: Vec<String> = elements
-                .iter()
-                .map(|elem| {
-                    default_value_for_type_expr(
-                        elem,
-                        current_module,
-                        context_module,
-                        type_lookup,
-                        imports,
-                        visited,
-                        uses_option_helpers,
-                    )
-                })
-                .collect();
-            format!("#({})", values.join(", "))
+            let mut values = Vec::new();
+            for elem in elements {
+                values.push(default_value_for_type_expr(
+                    elem,
+                    current_module,
+                    context_module,
+                    type_lookup,
+                    imports,
+                    visited,
+                    uses_option_helpers,
+                )?);
+            }
+            Some(format!("#({})", values.join(", ")))
         }
-        TypeExpression::Function { .. } => panic_default_message("function"),
-        TypeExpression::Var(_) => panic_default_message("type variable"),
-        TypeExpression::Hole => panic_default_message("type hole"),
+        TypeExpression::Function { .. } | TypeExpression::Var(_) | TypeExpression::Hole => None,
     }
 }
 
@@ -1009,18 +2842,62 @@ fn format_unknown_variant_message(
         .unwrap_or_else(|| default_expected.to_string())
 }
 
-fn convert_field_name(field_name: &str, naming: FieldNamingConvention) -> String {
+/// Render a Gleam field label or constructor name in the given naming convention
+fn convert_name(name: &str, naming: FieldNamingConvention) -> String {
     match naming {
-        FieldNamingConvention::SnakeCase => field_name.to_string(),
-        FieldNamingConvention::CamelCase => to_camel_case(field_name),
+        FieldNamingConvention::SnakeCase => to_snake_case(name),
+        FieldNamingConvention::CamelCase => to_camel_case(name),
+        FieldNamingConvention::KebabCase => split_words(name).join("-"),
+        FieldNamingConvention::PascalCase => split_words(name)
+            .iter()
+            .map(|word| capitalize(word))
+            .collect(),
+        FieldNamingConvention::ScreamingSnakeCase => split_words(name)
+            .iter()
+            .map(|word| word.to_uppercase())
+            .collect::<Vec<_>>()
+            .join("_"),
+    }
+}
+
+/// Split `user_id`, `UserId` or `HTTPRequest` into lowercase words
+fn split_words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+
+    for (index, &ch) in chars.iter().enumerate() {
+        if ch == '_' || ch == '-' {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if ch.is_uppercase() && !current.is_empty() {
+            let previous = chars[index - 1];
+            let next_is_lower = chars.get(index + 1).is_some_and(|c| c.is_lowercase());
+            // Break before `Id` in `UserId` and before `Request` in `HTTPRequest`
+            if !previous.is_uppercase() || next_is_lower {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+
+        current.extend(ch.to_lowercase());
+    }
+
+    if !current.is_empty() {
+        words.push(current);
     }
+    words
 }
 
-fn escape_gleam_string(value: &str) -> String {
-    value
-        .replace('\\', "\\\\")
-        .replace('"', "\\\"")
-        .replace('\n', "\\n")
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
 }
 
 fn to_snake_case(s: &str) -> String {
@@ -2,16 +2,26 @@ mod backend;
 mod config;
 mod generator;
 mod parser;
+mod plugin;
 
-pub use backend::{BackendRegistry, EncoderBackend, EncoderBackendRef, JsonEncoderBackend};
-pub use config::{AbsentFieldMode, Config, FieldNamingConvention, OutputConfig};
+pub use backend::{
+    BackendRegistry, DecoderBackend, DecoderBackendRef, DynamicDecoderBackend, EncoderBackend,
+    EncoderBackendRef, JsonEncoderBackend,
+};
+pub use config::{
+    AbsentEncoding, AbsentFieldMode, BackendSettings, BitArrayEncoding, Config, EnumRepresentation,
+    FieldNamingConvention, OutputConfig, PluginConfig,
+};
 pub use parser::{
-    parse_gleam_files, CustomTypeInfo, EncoderType, FieldInfo, FieldMarker, FileConfig,
-    OutputOverride, PathMode,
+    parse_gleam_files, parse_gleam_project, CustomTypeInfo, EncoderType, FieldDefault, FieldInfo,
+    FieldMarker, FieldValidation, FileConfig, OutputOverride, ParsedProject, PathMode,
+    TypeAliasInfo,
 };
 
 use camino::Utf8PathBuf;
 use generator::{generate_decoder, generate_encoder};
+use parser::{expand_type_aliases, TypeExpression};
+use plugin::run_plugin;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::Arc;
 use thiserror::Error;
@@ -55,8 +65,13 @@ pub struct ImportEntry {
 pub(crate) struct TypeRegistryEntry {
     pub(crate) module_path: String,
     pub(crate) generates_decoder: bool,
+    /// Name of the backend the decoder is generated with
+    pub(crate) decoder_backend: String,
     pub(crate) decoder_fn_name: Option<String>,
     pub(crate) encoder_fn_names: BTreeMap<String, String>,
+    pub(crate) parameters: Vec<String>,
+    /// Types sharing a group reference each other in a cycle and must decode lazily
+    pub(crate) recursion_group: Option<usize>,
 }
 
 pub(crate) type TypeRegistry = HashMap<String, HashMap<String, TypeRegistryEntry>>;
@@ -74,6 +89,8 @@ pub struct GeneratedCode {
     pub custom_imports: BTreeMap<String, ImportEntry>,
     /// Encoder backends used for this group, if any encoders were generated
     pub encoder_backends: BTreeMap<String, EncoderBackendRef>,
+    /// Decoder backends used for this group, if any decoders were generated
+    pub decoder_backends: BTreeMap<String, DecoderBackendRef>,
     /// Whether any decoder in this group uses option helpers
     pub decoder_uses_option_helpers: bool,
 }
@@ -90,6 +107,11 @@ impl Clone for GeneratedCode {
                 .iter()
                 .map(|(name, backend)| (name.clone(), Arc::clone(backend)))
                 .collect(),
+            decoder_backends: self
+                .decoder_backends
+                .iter()
+                .map(|(name, backend)| (name.clone(), Arc::clone(backend)))
+                .collect(),
             decoder_uses_option_helpers: self.decoder_uses_option_helpers,
         }
     }
@@ -106,6 +128,10 @@ impl std::fmt::Debug for GeneratedCode {
                 "encoder_backends",
                 &self.encoder_backends.keys().cloned().collect::<Vec<_>>(),
             )
+            .field(
+                "decoder_backends",
+                &self.decoder_backends.keys().cloned().collect::<Vec<_>>(),
+            )
             .field(
                 "decoder_uses_option_helpers",
                 &self.decoder_uses_option_helpers,
@@ -125,6 +151,14 @@ pub(crate) fn module_alias(module_path: &str) -> String {
     alias
 }
 
+/// Escape `value` for use between the quotes of a Gleam string literal
+pub(crate) fn escape_gleam_string(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
 impl ImportEntry {
     pub(crate) fn new(module_path: &str, alias: String) -> Self {
         Self {
@@ -151,8 +185,14 @@ fn build_type_registry(
                     TypeRegistryEntry {
                         module_path: type_info.module_path.clone(),
                         generates_decoder: type_info.generate_decoder,
+                        decoder_backend: type_info
+                            .decoder_backend
+                            .clone()
+                            .unwrap_or_else(|| DynamicDecoderBackend::NAME.to_string()),
                         decoder_fn_name: None,
                         encoder_fn_names: BTreeMap::new(),
+                        parameters: type_info.parameters.clone(),
+                        recursion_group: None,
                     },
                 );
         }
@@ -178,6 +218,150 @@ fn build_type_lookup(
     lookup
 }
 
+/// Find cycles in the graph of field references between annotated types (Tarjan's
+/// strongly connected components) and tag every type taking part in one.
+fn assign_recursion_groups(registry: &mut TypeRegistry, type_lookup: &TypeLookup) {
+    let mut keys: Vec<(String, String)> = type_lookup.keys().cloned().collect();
+    keys.sort();
+    let index_of: HashMap<(String, String), usize> = keys
+        .iter()
+        .enumerate()
+        .map(|(index, key)| (key.clone(), index))
+        .collect();
+
+    let edges: Vec<Vec<usize>> = keys
+        .iter()
+        .map(|key| {
+            let type_info = &type_lookup[key];
+            let mut targets = BTreeSet::new();
+            for field in type_info.constructors.iter().flat_map(|c| c.fields.iter()) {
+                if field.decoder_with.is_none() {
+                    collect_type_references(
+                        &field.type_expr,
+                        registry,
+                        &type_info.module_path,
+                        &index_of,
+                        &mut targets,
+                    );
+                }
+            }
+            targets.into_iter().collect()
+        })
+        .collect();
+
+    let mut groups: Vec<Option<usize>> = vec![None; keys.len()];
+    let mut tarjan = Tarjan {
+        edges: &edges,
+        next_index: 0,
+        indices: vec![None; keys.len()],
+        low_links: vec![0; keys.len()],
+        stack: Vec::new(),
+        on_stack: vec![false; keys.len()],
+        next_group: 0,
+        groups: &mut groups,
+    };
+    for node in 0..keys.len() {
+        if tarjan.indices[node].is_none() {
+            tarjan.visit(node);
+        }
+    }
+
+    for (key, group) in keys.iter().zip(groups) {
+        if let Some(entry) = registry.get_mut(&key.0).and_then(|m| m.get_mut(&key.1)) {
+            entry.recursion_group = group;
+        }
+    }
+}
+
+fn collect_type_references(
+    type_expr: &TypeExpression,
+    registry: &TypeRegistry,
+    current_module_path: &str,
+    index_of: &HashMap<(String, String), usize>,
+    targets: &mut BTreeSet<usize>,
+) {
+    match type_expr {
+        TypeExpression::Constructor {
+            module,
+            name,
+            arguments,
+        } => {
+            if let Some(entry) =
+                find_type_entry(registry, module.as_deref(), name, current_module_path)
+            {
+                if let Some(index) = index_of.get(&(entry.module_path.clone(), name.clone())) {
+                    targets.insert(*index);
+                }
+            }
+            for argument in arguments {
+                collect_type_references(argument, registry, current_module_path, index_of, targets);
+            }
+        }
+        TypeExpression::Tuple(elements) => {
+            for element in elements {
+                collect_type_references(element, registry, current_module_path, index_of, targets);
+            }
+        }
+        TypeExpression::Function { .. } | TypeExpression::Var(_) | TypeExpression::Hole => {}
+    }
+}
+
+struct Tarjan<'a> {
+    edges: &'a [Vec<usize>],
+    next_index: usize,
+    indices: Vec<Option<usize>>,
+    low_links: Vec<usize>,
+    stack: Vec<usize>,
+    on_stack: Vec<bool>,
+    next_group: usize,
+    groups: &'a mut [Option<usize>],
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, node: usize) {
+        self.indices[node] = Some(self.next_index);
+        self.low_links[node] = self.next_index;
+        self.next_index += 1;
+        self.stack.push(node);
+        self.on_stack[node] = true;
+
+        for &target in &self.edges[node] {
+            match self.indices[target] {
+                None => {
+                    self.visit(target);
+                    self.low_links[node] = self.low_links[node].min(self.low_links[target]);
+                }
+                Some(index) if self.on_stack[target] => {
+                    self.low_links[node] = self.low_links[node].min(index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(self.low_links[node]) != self.indices[node] {
+            return;
+        }
+
+        let mut component = Vec::new();
+        while let Some(member) = self.stack.pop() {
+            self.on_stack[member] = false;
+            component.push(member);
+            if member == node {
+                break;
+            }
+        }
+
+        // A single type only forms a cycle when it refers to itself
+        let is_cycle = component.len() > 1 || self.edges[node].contains(&node);
+        if is_cycle {
+            for member in component {
+                self.groups[member] = Some(self.next_group);
+            }
+            self.next_group += 1;
+        }
+    }
+}
+
 fn has_generated_encoders(
     custom_types: &HashMap<Utf8PathBuf, (FileConfig, Vec<CustomTypeInfo>)>,
 ) -> bool {
@@ -188,9 +372,9 @@ fn has_generated_encoders(
 
 fn ensure_backend_dependencies(
     project_root: &Utf8PathBuf,
-    backend: &dyn EncoderBackend,
+    required: &[&'static str],
+    generated: &str,
 ) -> Result<()> {
-    let required = backend.required_packages();
     if required.is_empty() {
         return Ok(());
     }
@@ -198,7 +382,8 @@ fn ensure_backend_dependencies(
     let gleam_toml_path = project_root.join("gleam.toml");
     if !gleam_toml_path.exists() {
         return Err(GlossError::GenerationError(format!(
-            "Generating encoders requires the `{}` dependency, but {} was not found",
+            "Generating {} requires the `{}` dependency, but {} was not found",
+            generated,
             required.join("`, `"),
             gleam_toml_path
         )));
@@ -218,8 +403,8 @@ fn ensure_backend_dependencies(
 
         if !present {
             return Err(GlossError::GenerationError(format!(
-                "Generating encoders requires the `{}` dependency. Add `{} = \"~> 1\"` (or your preferred version) to gleam.toml.",
-                package, package
+                "Generating {} requires the `{}` dependency. Add `{} = \"~> 1\"` (or your preferred version) to gleam.toml.",
+                generated, package, package
             )));
         }
     }
@@ -227,6 +412,21 @@ fn ensure_backend_dependencies(
     Ok(())
 }
 
+/// Backend registered for `encoder_type`, adjusted by its `[backend.<name>]` table
+fn configured_encoder_backend(
+    registry: &BackendRegistry,
+    encoder_type: &EncoderType,
+    config: &Config,
+) -> Result<EncoderBackendRef> {
+    let backend = registry.resolve(encoder_type)?;
+    match config.backend.get(encoder_type.identifier()) {
+        Some(settings) => Ok(backend
+            .configure(settings)?
+            .unwrap_or_else(|| Arc::clone(backend))),
+        None => Ok(Arc::clone(backend)),
+    }
+}
+
 fn dependency_table_contains(parsed: &toml::Value, section: &str, crate_name: &str) -> bool {
     parsed
         .get(section)
@@ -275,9 +475,8 @@ impl GeneratedCode {
         if has_imports {
             let import_map = self.build_import_map(include_type_imports);
             let import_block = generate_imports(
-                true,
+                Some(&self.decoder_backends),
                 self.decoder_uses_option_helpers,
-                false,
                 None,
                 &import_map,
             );
@@ -307,17 +506,8 @@ impl GeneratedCode {
 
         if has_imports {
             let import_map = self.build_import_map(include_type_imports);
-            let import_block = generate_imports(
-                false,
-                false,
-                true,
-                if self.encoder_backends.is_empty() {
-                    None
-                } else {
-                    Some(&self.encoder_backends)
-                },
-                &import_map,
-            );
+            let import_block =
+                generate_imports(None, false, Some(&self.encoder_backends), &import_map);
             if !import_block.is_empty() {
                 code.push_str(&import_block);
                 code.push_str("\n\n");
@@ -348,14 +538,9 @@ impl GeneratedCode {
         if has_imports && (has_decoder || has_encoder) {
             let import_map = self.build_import_map(include_type_imports);
             let import_block = generate_imports(
-                has_decoder,
+                has_decoder.then_some(&self.decoder_backends),
                 self.decoder_uses_option_helpers,
-                has_encoder,
-                if has_encoder && !self.encoder_backends.is_empty() {
-                    Some(&self.encoder_backends)
-                } else {
-                    None
-                },
+                has_encoder.then_some(&self.encoder_backends),
                 &import_map,
             );
             if !import_block.is_empty() {
@@ -401,30 +586,47 @@ pub fn generate_for_project(
     root_path: &Utf8PathBuf,
     registry: &BackendRegistry,
 ) -> Result<HashMap<Utf8PathBuf, Vec<GeneratedCode>>> {
-    let mut custom_types = parse_gleam_files(root_path)?;
+    let ParsedProject {
+        types: mut custom_types,
+        aliases,
+    } = parse_gleam_project(root_path)?;
+    expand_type_aliases(&mut custom_types, &aliases)?;
 
     if has_generated_encoders(&custom_types) {
-        let mut used_encoders = std::collections::HashSet::new();
-        for (_file, (_cfg, types)) in custom_types.iter() {
+        let mut required_packages = BTreeSet::new();
+        for (file_path, (_cfg, types)) in custom_types.iter() {
+            let config = Config::load_cascaded(root_path, file_path);
             for type_info in types {
                 for encoder_type in &type_info.encoders {
-                    used_encoders.insert(*encoder_type);
+                    // Names missing from the registry are served by `[backends.<name>]` plugins
+                    if registry.get(encoder_type).is_none()
+                        && config.backends.contains_key(encoder_type.identifier())
+                    {
+                        continue;
+                    }
+                    let backend = configured_encoder_backend(registry, encoder_type, &config)?;
+                    required_packages.extend(backend.required_packages().iter().copied());
                 }
             }
         }
 
-        for encoder_type in used_encoders {
-            let backend = registry.get(encoder_type).ok_or_else(|| {
-                GlossError::GenerationError(format!(
-                    "No encoder backend registered for {:?}",
-                    encoder_type
-                ))
-            })?;
-            ensure_backend_dependencies(root_path, backend.as_ref())?;
+        let required_packages: Vec<&'static str> = required_packages.into_iter().collect();
+        ensure_backend_dependencies(root_path, &required_packages, "encoders")?;
+    }
+
+    let mut used_decoders = BTreeSet::new();
+    for (_file, (_cfg, types)) in custom_types.iter() {
+        for type_info in types.iter().filter(|type_info| type_info.generate_decoder) {
+            used_decoders.insert(type_info.decoder_backend.as_deref());
         }
     }
+    for name in used_decoders {
+        let backend = registry.resolve_decoder(name)?;
+        ensure_backend_dependencies(root_path, backend.required_packages(), "decoders")?;
+    }
     let mut type_registry = build_type_registry(&custom_types);
     let type_lookup = build_type_lookup(&custom_types);
+    assign_recursion_groups(&mut type_registry, &type_lookup);
     let mut outputs = HashMap::new();
 
     // Precompute generated function names for all types so cross-file references can use them.
@@ -456,7 +658,7 @@ pub fn generate_for_project(
                     None
                 };
                 entry.encoder_fn_names.clear();
-                let mut unique_backends: Vec<&'static str> = Vec::new();
+                let mut unique_backends: Vec<&str> = Vec::new();
                 for encoder_type in &type_info.encoders {
                     let backend_id = encoder_type.identifier();
                     if !unique_backends.contains(&backend_id) {
@@ -574,6 +776,7 @@ pub fn generate_for_project(
             let mut encoder = None;
             let mut type_imports: BTreeMap<String, ImportEntry> = BTreeMap::new();
             let mut encoder_backends: BTreeMap<String, EncoderBackendRef> = BTreeMap::new();
+            let mut decoder_backends: BTreeMap<String, DecoderBackendRef> = BTreeMap::new();
             let mut decoder_uses_option_helpers = false;
 
             let type_config = ctx.config;
@@ -582,6 +785,9 @@ pub fn generate_for_project(
 
             // Generate decoder if requested
             if type_info.generate_decoder {
+                let backend_arc = registry.resolve_decoder(type_info.decoder_backend.as_deref())?;
+                decoder_backends.insert(backend_arc.name().to_string(), backend_arc.clone());
+
                 let decoder_output = generate_decoder(
                     &type_info,
                     &type_config,
@@ -589,6 +795,7 @@ pub fn generate_for_project(
                     &mut type_imports,
                     &type_lookup,
                     unknown_message.as_deref(),
+                    backend_arc.as_ref(),
                 )?;
                 decoder_uses_option_helpers = decoder_output.uses_option_helpers;
                 decoder = Some(decoder_output.code);
@@ -598,15 +805,45 @@ pub fn generate_for_project(
             if !type_info.encoders.is_empty() {
                 let mut encoder_code = String::new();
                 for encoder_type in &type_info.encoders {
-                    let backend_arc = registry
-                        .get(*encoder_type)
-                        .ok_or_else(|| {
-                            GlossError::GenerationError(format!(
-                                "No encoder backend registered for {:?}",
-                                encoder_type
-                            ))
-                        })?
-                        .clone();
+                    let backend_arc = match (
+                        registry.get(encoder_type),
+                        type_config.backends.get(encoder_type.identifier()),
+                    ) {
+                        (None, Some(plugin)) => {
+                            let backend_id = encoder_type.identifier();
+                            let fn_name = type_registry
+                                .get(&type_info.module_path)
+                                .and_then(|types| types.get(&type_info.name))
+                                .and_then(|entry| entry.encoder_fn_names.get(backend_id))
+                                .cloned()
+                                .unwrap_or_else(|| {
+                                    type_config
+                                        .fn_naming
+                                        .render_encoder_fn_name(&type_info.name, backend_id)
+                                });
+                            let settings = type_config
+                                .backend
+                                .get(backend_id)
+                                .cloned()
+                                .unwrap_or_default();
+                            encoder_code.push_str(&run_plugin(
+                                root_path, backend_id, plugin, &settings, &fn_name, &type_info,
+                            )?);
+                            encoder_code.push_str("\n\n");
+                            for module_path in &plugin.imports {
+                                let alias = module_path
+                                    .rsplit('/')
+                                    .next()
+                                    .unwrap_or(module_path)
+                                    .to_string();
+                                type_imports
+                                    .entry(module_path.clone())
+                                    .or_insert_with(|| ImportEntry::new(module_path, alias));
+                            }
+                            continue;
+                        }
+                        _ => configured_encoder_backend(registry, encoder_type, &type_config)?,
+                    };
 
                     let backend_name = backend_arc.name().to_string();
                     encoder_backends
@@ -615,10 +852,11 @@ pub fn generate_for_project(
 
                     encoder_code.push_str(&generate_encoder(
                         &type_info,
-                        *encoder_type,
+                        encoder_type,
                         &type_config,
                         &type_registry,
                         &mut type_imports,
+                        &type_lookup,
                         backend_arc.as_ref(),
                     )?);
                     encoder_code.push_str("\n\n");
@@ -651,6 +889,12 @@ pub fn generate_for_project(
                             .entry(name.clone())
                             .or_insert_with(|| backend.clone());
                     }
+                    for (name, backend) in &decoder_backends {
+                        existing
+                            .decoder_backends
+                            .entry(name.clone())
+                            .or_insert_with(|| backend.clone());
+                    }
                     existing.decoder_uses_option_helpers |= decoder_uses_option_helpers;
                     if existing.decoder_uses_option_helpers {
                         ensure_no_option_alias_conflict(&existing.custom_imports)?;
@@ -665,6 +909,7 @@ pub fn generate_for_project(
                         path_mode: type_path_mode,
                         custom_imports: type_imports,
                         encoder_backends: encoder_backends.clone(),
+                        decoder_backends: decoder_backends.clone(),
                         decoder_uses_option_helpers: decoder_uses_option_helpers,
                     });
                 }
@@ -714,26 +959,25 @@ fn ensure_no_option_alias_conflict(imports: &BTreeMap<String, ImportEntry>) -> R
 
 /// Generate necessary imports based on what's being generated
 fn generate_imports(
-    has_decoder: bool,
+    decoder_backends: Option<&BTreeMap<String, DecoderBackendRef>>,
     decoder_uses_option_helpers: bool,
-    has_encoder: bool,
     encoder_backends: Option<&BTreeMap<String, EncoderBackendRef>>,
     custom_imports: &BTreeMap<String, ImportEntry>,
 ) -> String {
     let mut imports: Vec<String> = Vec::new();
 
-    if has_decoder {
-        imports.push("import gleam/dynamic/decode".to_string());
+    if let Some(backends) = decoder_backends {
+        for backend in backends.values() {
+            imports.extend(backend.module_imports());
+        }
         if decoder_uses_option_helpers {
             imports.push("import gleam/option".to_string());
         }
     }
 
-    if has_encoder {
-        if let Some(backends) = encoder_backends {
-            for backend in backends.values() {
-                imports.extend(backend.module_imports());
-            }
+    if let Some(backends) = encoder_backends {
+        for backend in backends.values() {
+            imports.extend(backend.module_imports());
         }
     }
 
@@ -767,7 +1011,9 @@ fn generate_imports(
             line.push_str(&format!(" as {}", entry.alias));
         }
 
-        imports.push(line);
+        if !imports.contains(&line) {
+            imports.push(line);
+        }
     }
 
     imports.join("\n")
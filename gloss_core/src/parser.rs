@@ -1,10 +1,11 @@
-use crate::config::{FieldNamingConvention, FnNamingOverride};
+use crate::config::{AbsentEncoding, EnumRepresentation, FieldNamingConvention, FnNamingOverride};
 use crate::{GlossError, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use gleam_core::ast::{self, AssignName};
 use gleam_core::warning::WarningEmitter;
 use regex::Regex;
-use std::collections::{BTreeSet, HashMap};
+use serde::{Serialize, Serializer};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 /// Output configuration that can be specified at file or type level
 #[derive(Debug, Clone, Default)]
@@ -60,21 +61,37 @@ impl OutputOverride {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CustomTypeInfo {
     pub name: String,
+    /// Type parameters in declaration order, e.g. `["item"]` for `Page(item)`
+    pub parameters: Vec<String>,
     pub constructors: Vec<ConstructorInfo>,
     pub encoders: Vec<EncoderType>,
     pub generate_decoder: bool,
+    /// Decoder backend requested by `decoder(<name>)`; `None` uses `gleam/dynamic/decode`
+    pub decoder_backend: Option<String>,
     pub field_naming_strategy: Option<FieldNamingConvention>,
+    pub variant_naming_strategy: Option<FieldNamingConvention>,
     pub module_name: String,
     pub module_path: String,
     pub type_tag_field: Option<String>, // Custom type tag field name (default: "type")
     pub disable_type_tag: bool,         // If true, don't use type tags
+    pub representation: Option<EnumRepresentation>,
+    pub content_field: Option<String>, // Key holding adjacently tagged fields
+    pub int_enum: bool, // Fieldless variants are written as integers
+    pub deny_unknown_fields: bool, // Decoders reject keys that no field reads
+    #[serde(skip)]
     pub output_override: Option<OutputOverride>, // Type-level output configuration
     pub unknown_variant_message: Option<String>,
+    #[serde(skip)]
     pub fn_naming_override: Option<FnNamingOverride>,
+    #[serde(skip)]
     pub option_availability: OptionAvailability,
+    #[serde(skip)]
+    pub dict_availability: DictAvailability,
+    /// Import aliases visible in the declaring module, mapped to their module paths
+    pub module_imports: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -83,6 +100,13 @@ pub struct OptionAvailability {
     pub aliases: BTreeSet<String>,
 }
 
+/// Tracks how `gleam/dict.Dict` can be referenced within a module
+#[derive(Debug, Clone, Default)]
+pub struct DictAvailability {
+    pub unqualified: bool,
+    pub aliases: BTreeSet<String>,
+}
+
 /// File-level configuration
 #[derive(Debug, Clone, Default)]
 pub struct FileConfig {
@@ -91,26 +115,59 @@ pub struct FileConfig {
     pub fn_naming_override: Option<FnNamingOverride>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Encoder backend requested by `encoder(<name>)`, resolved against the `BackendRegistry`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum EncoderType {
     Json,
+    /// Backend registered under any other name
+    Named(String),
 }
 
 impl EncoderType {
-    pub fn identifier(self) -> &'static str {
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "json" | "JSON" => EncoderType::Json,
+            _ => EncoderType::Named(name.to_string()),
+        }
+    }
+
+    pub fn identifier(&self) -> &str {
         match self {
             EncoderType::Json => "json",
+            EncoderType::Named(name) => name,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+impl From<&str> for EncoderType {
+    fn from(name: &str) -> Self {
+        EncoderType::from_name(name)
+    }
+}
+
+impl Serialize for EncoderType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.identifier())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct ConstructorInfo {
     pub name: String,
     pub fields: Vec<FieldInfo>,
+    /// Explicit tag written to and read from the wire (default: snake_case constructor name)
+    pub tag: Option<String>,
+    /// Variant is never produced by the decoder; encoders still write it
+    pub skip: bool,
+    /// Variant is still accepted but no longer advertised in error messages
+    pub deprecated: bool,
+    /// Variant receives every unrecognised tag instead of failing the decoder
+    pub fallback: bool,
+    /// Explicit integer for `int_enum` types (default: previous value + 1, starting at 0)
+    pub value: Option<i64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FieldInfo {
     pub label: String,
     pub type_: String,
@@ -120,11 +177,97 @@ pub struct FieldInfo {
     pub custom_name: Option<String>, // Custom JSON field name
     pub decoder_with: Option<String>,
     pub encoder_with: Option<String>,
+    /// Value used when the key is absent from the input
+    pub default: Option<FieldDefault>,
+    /// Field is left out of the encoded object
+    pub skip_encoding: bool,
+    /// Field is never read from the input and is filled from its default instead
+    pub skip_decoding: bool,
+    /// Overrides the project-wide `absent_encoding` for this `Option(T)` field
+    pub absent_encoding: Option<AbsentEncoding>,
+    /// Fields of this nested record are merged into the parent object
+    pub flatten: bool,
+    /// Additional keys accepted when decoding, tried in order after the primary key
+    pub aliases: Vec<String>,
+    /// Keys leading to the value through nested objects (`path = "data.user.id"`)
+    pub path: Vec<String>,
+    /// Dictionary collecting every key no other field reads
+    pub extra: bool,
+    /// Constraints checked after decoding
+    pub validation: FieldValidation,
+}
+
+/// Constraints a decoded field value has to satisfy
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FieldValidation {
+    /// Inclusive lower bound of an `Int` or `Float` field, as written in the annotation
+    pub min: Option<String>,
+    /// Inclusive upper bound of an `Int` or `Float` field, as written in the annotation
+    pub max: Option<String>,
+    /// Minimum length of a `String` or `List` field
+    pub min_length: Option<usize>,
+    /// Maximum length of a `String` or `List` field
+    pub max_length: Option<usize>,
+    /// Allowed values of a `String` field
+    pub one_of: Vec<String>,
+    /// Function of type `fn(a) -> Result(a, String)` run on the decoded value
+    pub validate_with: Option<String>,
+}
+
+impl FieldValidation {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
 }
 
+/// Fallback value for a field whose key is missing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldDefault {
+    /// Gleam expression written in the context of the declaring module, e.g. `3` or `[]`
+    Expression(String),
+    /// Zero-argument function reference, e.g. `"settings.default_retries"`
+    Function(String),
+}
+
+impl TypeExpression {
+    /// Whether this is `Option(a)` from `gleam/option`
+    pub fn is_standard_option(&self) -> bool {
+        matches!(
+            self,
+            TypeExpression::Constructor {
+                module: Some(module_path),
+                name,
+                ..
+            } if name == "Option" && module_path == "gleam/option"
+        )
+    }
+}
+
+/// A `pub type X = ...` alias, expanded in place of references to it before generation
 #[derive(Debug, Clone)]
+pub struct TypeAliasInfo {
+    pub name: String,
+    pub module_path: String,
+    pub parameters: Vec<String>,
+    pub target: TypeExpression,
+}
+
+/// Everything gloss collects from a project's sources
+#[derive(Debug, Clone, Default)]
+pub struct ParsedProject {
+    /// Annotated custom types grouped by file
+    pub types: HashMap<Utf8PathBuf, (FileConfig, Vec<CustomTypeInfo>)>,
+    /// Type aliases declared in any module, annotated or not
+    pub aliases: Vec<TypeAliasInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TypeExpression {
     Constructor {
+        /// Full path of the module declaring the type, e.g. `models/user`; `None` for prelude
+        /// types and types declared in the module the expression is written in
         module: Option<String>,
         name: String,
         arguments: Vec<TypeExpression>,
@@ -138,7 +281,8 @@ pub enum TypeExpression {
     Hole,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FieldMarker {
     /// Field is required (not optional, cannot be absent) — use `must_exist`
     Required,
@@ -152,8 +296,13 @@ pub enum FieldMarker {
 pub fn parse_gleam_files(
     root_path: &Utf8PathBuf,
 ) -> Result<HashMap<Utf8PathBuf, (FileConfig, Vec<CustomTypeInfo>)>> {
+    Ok(parse_gleam_project(root_path)?.types)
+}
+
+/// Parse Gleam source files, collecting annotated custom types and every type alias
+pub fn parse_gleam_project(root_path: &Utf8PathBuf) -> Result<ParsedProject> {
     let src_dir = root_path.join("src");
-    let mut custom_types_by_file = HashMap::new();
+    let mut project = ParsedProject::default();
 
     // Find all .gleam files
     let gleam_files = find_gleam_files(&src_dir)?;
@@ -166,14 +315,223 @@ pub fn parse_gleam_files(
         })?;
         let module_path = relative_path.with_extension("").to_string();
 
-        let (file_config, types) = parse_file(&file_path, &module_path, &source)?;
+        let (file_config, types, aliases) = parse_file(&file_path, &module_path, &source)?;
+        project.aliases.extend(aliases);
 
         if !types.is_empty() {
-            custom_types_by_file.insert(file_path, (file_config, types));
+            project.types.insert(file_path, (file_config, types));
+        }
+    }
+
+    Ok(project)
+}
+
+/// Types that are always in scope and never need a module qualifier
+const PRELUDE_TYPES: &[&str] = &[
+    "BitArray",
+    "Bool",
+    "Float",
+    "Int",
+    "List",
+    "Nil",
+    "Result",
+    "String",
+    "UtfCodepoint",
+];
+
+/// Upper bound on nested alias expansion, guarding against alias cycles
+const MAX_ALIAS_DEPTH: usize = 64;
+
+/// Replace every reference to a type alias in annotated fields with the aliased type
+pub(crate) fn expand_type_aliases(
+    custom_types: &mut HashMap<Utf8PathBuf, (FileConfig, Vec<CustomTypeInfo>)>,
+    aliases: &[TypeAliasInfo],
+) -> Result<()> {
+    if aliases.is_empty() {
+        return Ok(());
+    }
+
+    for (_file_config, types) in custom_types.values_mut() {
+        for type_info in types.iter_mut() {
+            let scope = type_info.module_path.clone();
+            for field in type_info
+                .constructors
+                .iter_mut()
+                .flat_map(|constructor| constructor.fields.iter_mut())
+            {
+                field.type_expr = expand_aliases_in(&field.type_expr, &scope, aliases, 0)?;
+                field.is_option = field.type_expr.is_standard_option();
+            }
         }
     }
 
-    Ok(custom_types_by_file)
+    Ok(())
+}
+
+/// `scope` is the module the expression is written in, which unqualified names refer to
+fn expand_aliases_in(
+    type_expr: &TypeExpression,
+    scope: &str,
+    aliases: &[TypeAliasInfo],
+    depth: usize,
+) -> Result<TypeExpression> {
+    match type_expr {
+        TypeExpression::Constructor {
+            module,
+            name,
+            arguments,
+        } => {
+            let arguments = arguments
+                .iter()
+                .map(|argument| expand_aliases_in(argument, scope, aliases, depth))
+                .collect::<Result<Vec<_>>>()?;
+
+            let alias = match find_type_alias(aliases, module.as_deref(), name, scope) {
+                Some(alias) => alias,
+                None => {
+                    return Ok(TypeExpression::Constructor {
+                        module: module.clone(),
+                        name: name.clone(),
+                        arguments,
+                    })
+                }
+            };
+
+            if depth >= MAX_ALIAS_DEPTH {
+                return Err(GlossError::GenerationError(format!(
+                    "Type alias `{}` could not be expanded; does it refer to itself?",
+                    name
+                )));
+            }
+            if alias.parameters.len() != arguments.len() {
+                return Err(GlossError::GenerationError(format!(
+                    "Type alias `{}` expects {} type argument(s) but {} were given",
+                    name,
+                    alias.parameters.len(),
+                    arguments.len()
+                )));
+            }
+
+            let target = if alias.module_path == scope {
+                alias.target.clone()
+            } else {
+                qualify_alias_target(&alias.target, &alias.module_path)
+            };
+            let substitutions: HashMap<&str, &TypeExpression> = alias
+                .parameters
+                .iter()
+                .map(String::as_str)
+                .zip(arguments.iter())
+                .collect();
+            let substituted = substitute_type_vars(&target, &substitutions);
+            expand_aliases_in(&substituted, scope, aliases, depth + 1)
+        }
+        TypeExpression::Tuple(elements) => Ok(TypeExpression::Tuple(
+            elements
+                .iter()
+                .map(|element| expand_aliases_in(element, scope, aliases, depth))
+                .collect::<Result<Vec<_>>>()?,
+        )),
+        TypeExpression::Function {
+            arguments,
+            return_type,
+        } => Ok(TypeExpression::Function {
+            arguments: arguments
+                .iter()
+                .map(|argument| expand_aliases_in(argument, scope, aliases, depth))
+                .collect::<Result<Vec<_>>>()?,
+            return_type: Box::new(expand_aliases_in(return_type, scope, aliases, depth)?),
+        }),
+        TypeExpression::Var(_) | TypeExpression::Hole => Ok(type_expr.clone()),
+    }
+}
+
+fn find_type_alias<'a>(
+    aliases: &'a [TypeAliasInfo],
+    module: Option<&str>,
+    name: &str,
+    scope: &str,
+) -> Option<&'a TypeAliasInfo> {
+    let module_path = module.unwrap_or(scope);
+    aliases
+        .iter()
+        .find(|alias| alias.name == name && alias.module_path == module_path)
+}
+
+/// Types declared next to an alias are written unqualified in its target, so they gain
+/// the alias module's path before the alias is expanded elsewhere.
+fn qualify_alias_target(type_expr: &TypeExpression, alias_module: &str) -> TypeExpression {
+    let qualify = |expr: &TypeExpression| qualify_alias_target(expr, alias_module);
+
+    match type_expr {
+        TypeExpression::Constructor {
+            module,
+            name,
+            arguments,
+        } => {
+            let module = match module {
+                None if !PRELUDE_TYPES.contains(&name.as_str()) => Some(alias_module.to_string()),
+                _ => module.clone(),
+            };
+            TypeExpression::Constructor {
+                module,
+                name: name.clone(),
+                arguments: arguments.iter().map(qualify).collect(),
+            }
+        }
+        TypeExpression::Tuple(elements) => {
+            TypeExpression::Tuple(elements.iter().map(qualify).collect())
+        }
+        TypeExpression::Function {
+            arguments,
+            return_type,
+        } => TypeExpression::Function {
+            arguments: arguments.iter().map(qualify).collect(),
+            return_type: Box::new(qualify(return_type)),
+        },
+        TypeExpression::Var(_) | TypeExpression::Hole => type_expr.clone(),
+    }
+}
+
+fn substitute_type_vars(
+    type_expr: &TypeExpression,
+    substitutions: &HashMap<&str, &TypeExpression>,
+) -> TypeExpression {
+    match type_expr {
+        TypeExpression::Var(name) => substitutions
+            .get(name.as_str())
+            .map(|replacement| (*replacement).clone())
+            .unwrap_or_else(|| type_expr.clone()),
+        TypeExpression::Constructor {
+            module,
+            name,
+            arguments,
+        } => TypeExpression::Constructor {
+            module: module.clone(),
+            name: name.clone(),
+            arguments: arguments
+                .iter()
+                .map(|argument| substitute_type_vars(argument, substitutions))
+                .collect(),
+        },
+        TypeExpression::Tuple(elements) => TypeExpression::Tuple(
+            elements
+                .iter()
+                .map(|element| substitute_type_vars(element, substitutions))
+                .collect(),
+        ),
+        TypeExpression::Function {
+            arguments,
+            return_type,
+        } => TypeExpression::Function {
+            arguments: arguments
+                .iter()
+                .map(|argument| substitute_type_vars(argument, substitutions))
+                .collect(),
+            return_type: Box::new(substitute_type_vars(return_type, substitutions)),
+        },
+        TypeExpression::Hole => TypeExpression::Hole,
+    }
 }
 
 fn find_gleam_files(dir: &Utf8Path) -> Result<Vec<Utf8PathBuf>> {
@@ -202,7 +560,7 @@ fn parse_file(
     file_path: &Utf8Path,
     module_path: &str,
     source: &str,
-) -> Result<(FileConfig, Vec<CustomTypeInfo>)> {
+) -> Result<(FileConfig, Vec<CustomTypeInfo>, Vec<TypeAliasInfo>)> {
     // Parse file-level configuration
     let file_config = parse_file_level_config(source);
 
@@ -212,10 +570,13 @@ fn parse_file(
         .map_err(|e| GlossError::ParseError(format!("{:?}", e)))?;
 
     let mut custom_types = Vec::new();
+    let mut aliases = Vec::new();
 
     // Extract module name from file path
     let module_name = file_path.file_stem().unwrap_or("unknown").to_string();
-    let option_availability = compute_option_availability(&parsed.module)?;
+    let (option_availability, dict_availability, module_imports) =
+        compute_import_availability(&parsed.module)?;
+    let type_imports = unqualified_type_imports(&parsed.module);
 
     // Look for custom types with @gloss annotations in comments
     for definition in &parsed.module.definitions {
@@ -230,20 +591,48 @@ fn parse_file(
                 &module_name,
                 module_path,
                 &option_availability,
+                &dict_availability,
+                &module_imports,
+                &type_imports,
             )?;
             if !info.encoders.is_empty() || info.generate_decoder {
                 custom_types.push(info);
             }
+        } else if let ast::TargetedDefinition {
+            definition: ast::UntypedDefinition::TypeAlias(type_alias),
+            ..
+        } = definition
+        {
+            aliases.push(TypeAliasInfo {
+                name: type_alias.alias.to_string(),
+                module_path: module_path.to_string(),
+                parameters: type_alias
+                    .parameters
+                    .iter()
+                    .map(|(_, name)| name.to_string())
+                    .collect(),
+                target: resolve_type_modules(
+                    &type_ast_to_expression(
+                        &type_alias.type_ast,
+                        &option_availability,
+                        &dict_availability,
+                    ),
+                    &module_imports,
+                    &type_imports,
+                ),
+            });
         }
     }
 
-    Ok((file_config, custom_types))
+    Ok((file_config, custom_types, aliases))
 }
 
-fn compute_option_availability(
+fn compute_import_availability(
     module: &ast::Module<(), ast::TargetedDefinition>,
-) -> Result<OptionAvailability> {
+) -> Result<(OptionAvailability, DictAvailability, BTreeMap<String, String>)> {
     let mut availability = OptionAvailability::default();
+    let mut dict_availability = DictAvailability::default();
+    let mut module_imports = BTreeMap::new();
     let mut other_unqualified_sources: BTreeSet<String> = BTreeSet::new();
 
     for definition in &module.definitions {
@@ -253,26 +642,32 @@ fn compute_option_availability(
         } = definition
         {
             let module_path = import.module.to_string();
+            let alias = import
+                .as_name
+                .as_ref()
+                .and_then(|(assign, _)| match assign {
+                    AssignName::Variable(name) => Some(name.to_string()),
+                    _ => None,
+                })
+                .unwrap_or_else(|| {
+                    import
+                        .module
+                        .split('/')
+                        .next_back()
+                        .map(|value| value.to_string())
+                        .unwrap_or_else(|| module_path.clone())
+                });
 
             if module_path == "gleam/option" {
-                let alias = import
-                    .as_name
-                    .as_ref()
-                    .and_then(|(assign, _)| match assign {
-                        AssignName::Variable(name) => Some(name.to_string()),
-                        _ => None,
-                    })
-                    .unwrap_or_else(|| {
-                        import
-                            .module
-                            .split('/')
-                            .next_back()
-                            .map(|value| value.to_string())
-                            .unwrap_or_else(|| "option".to_string())
-                    });
-                availability.aliases.insert(alias);
+                availability.aliases.insert(alias.clone());
+            }
+
+            if module_path == "gleam/dict" {
+                dict_availability.aliases.insert(alias.clone());
             }
 
+            module_imports.insert(alias, module_path.clone());
+
             for unqualified in &import.unqualified_types {
                 if unqualified.name.as_str() == "Option" {
                     if module_path == "gleam/option" {
@@ -281,6 +676,10 @@ fn compute_option_availability(
                         other_unqualified_sources.insert(module_path.clone());
                     }
                 }
+
+                if unqualified.name.as_str() == "Dict" && module_path == "gleam/dict" {
+                    dict_availability.unqualified = true;
+                }
             }
         }
     }
@@ -297,7 +696,33 @@ fn compute_option_availability(
         availability.unqualified = true;
     }
 
-    Ok(availability)
+    Ok((availability, dict_availability, module_imports))
+}
+
+/// Types brought into scope with `import module.{type Name}`, mapped to their module paths
+fn unqualified_type_imports(
+    module: &ast::Module<(), ast::TargetedDefinition>,
+) -> BTreeMap<String, String> {
+    let mut type_imports = BTreeMap::new();
+
+    for definition in &module.definitions {
+        if let ast::TargetedDefinition {
+            definition: ast::UntypedDefinition::Import(import),
+            ..
+        } = definition
+        {
+            for unqualified in &import.unqualified_types {
+                let name = unqualified
+                    .as_name
+                    .as_ref()
+                    .unwrap_or(&unqualified.name)
+                    .to_string();
+                type_imports.insert(name, import.module.to_string());
+            }
+        }
+    }
+
+    type_imports
 }
 
 fn extract_custom_type_info(
@@ -306,6 +731,9 @@ fn extract_custom_type_info(
     module_name: &str,
     module_path: &str,
     option_availability: &OptionAvailability,
+    dict_availability: &DictAvailability,
+    module_imports: &BTreeMap<String, String>,
+    type_imports: &BTreeMap<String, String>,
 ) -> Result<CustomTypeInfo> {
     // Check for gloss!: annotations in the doc comment
     let annotations = if let Some((_, doc)) = &custom_type.documentation {
@@ -317,26 +745,42 @@ fn extract_custom_type_info(
         parse_gloss_annotations(&doc)
     };
 
-    let constructors = custom_type
+    let mut constructors = custom_type
         .constructors
         .iter()
-        .map(|c| extract_constructor_info(c, source, option_availability))
+        .map(|c| extract_constructor_info(c, source, option_availability, dict_availability))
         .collect::<Result<Vec<_>>>()?;
+    for field in constructors.iter_mut().flat_map(|c| c.fields.iter_mut()) {
+        field.type_expr = resolve_type_modules(&field.type_expr, module_imports, type_imports);
+    }
 
     Ok(CustomTypeInfo {
         name: custom_type.name.to_string(),
+        parameters: custom_type
+            .parameters
+            .iter()
+            .map(|(_, name)| name.to_string())
+            .collect(),
         constructors,
         encoders: annotations.encoders,
         generate_decoder: annotations.generate_decoder,
+        decoder_backend: annotations.decoder_backend,
         field_naming_strategy: annotations.field_naming_strategy,
+        variant_naming_strategy: annotations.variant_naming_strategy,
         type_tag_field: annotations.type_tag_field,
         disable_type_tag: annotations.disable_type_tag,
+        representation: annotations.representation,
+        content_field: annotations.content_field,
+        int_enum: annotations.int_enum,
+        deny_unknown_fields: annotations.deny_unknown_fields,
         module_path: module_path.to_string(),
         module_name: module_name.to_string(),
         output_override: annotations.output_override,
         unknown_variant_message: annotations.unknown_variant_message,
         fn_naming_override: annotations.fn_naming_override,
         option_availability: option_availability.clone(),
+        dict_availability: dict_availability.clone(),
+        module_imports: module_imports.clone(),
     })
 }
 
@@ -344,16 +788,30 @@ fn extract_constructor_info(
     constructor: &ast::RecordConstructor<()>,
     source: &str,
     option_availability: &OptionAvailability,
+    dict_availability: &DictAvailability,
 ) -> Result<ConstructorInfo> {
     let fields = constructor
         .arguments
         .iter()
-        .map(|arg| extract_field_info(arg, source, option_availability))
+        .map(|arg| extract_field_info(arg, source, option_availability, dict_availability))
         .collect::<Result<Vec<_>>>()?;
 
+    let annotations = if let Some((_, doc)) = &constructor.documentation {
+        parse_variant_annotations(doc)
+    } else {
+        let constructor_start = constructor.location.start as usize;
+        let comment = extract_comment_before(source, constructor_start);
+        parse_variant_annotations(&comment)
+    };
+
     Ok(ConstructorInfo {
         name: constructor.name.to_string(),
         fields,
+        tag: annotations.tag,
+        skip: annotations.skip,
+        deprecated: annotations.deprecated,
+        fallback: annotations.fallback,
+        value: annotations.value,
     })
 }
 
@@ -361,6 +819,7 @@ fn extract_field_info(
     arg: &ast::RecordConstructorArg<()>,
     source: &str,
     option_availability: &OptionAvailability,
+    dict_availability: &DictAvailability,
 ) -> Result<FieldInfo> {
     let label = arg
         .label
@@ -369,15 +828,8 @@ fn extract_field_info(
         .unwrap_or_else(|| "_unlabeled".to_string());
 
     let type_str = type_ast_to_string(&arg.ast);
-    let type_expr = type_ast_to_expression(&arg.ast, option_availability);
-    let is_option = matches!(
-        &type_expr,
-        TypeExpression::Constructor {
-            module: Some(module_path),
-            name,
-            ..
-        } if name == "Option" && module_path == "gleam/option"
-    );
+    let type_expr = type_ast_to_expression(&arg.ast, option_availability, dict_availability);
+    let is_option = type_expr.is_standard_option();
 
     // Look for marker comments before the field
     let annotations = if let Some((_, doc)) = &arg.doc {
@@ -389,6 +841,30 @@ fn extract_field_info(
         parse_field_annotations(&comment)
     };
 
+    // A `path` replaces the field's key entirely
+    if !annotations.path.is_empty() {
+        let invalid_path = |reason: &str| {
+            GlossError::GenerationError(format!(
+                "Field `{}` has an invalid `path`: {}",
+                label, reason
+            ))
+        };
+        if annotations.path.iter().any(String::is_empty) {
+            return Err(invalid_path("keys between the dots cannot be empty"));
+        }
+        let conflicting = [
+            ("rename", annotations.custom_name.is_some()),
+            ("flatten", annotations.flatten),
+            ("extra", annotations.extra),
+        ];
+        if let Some((annotation, _)) = conflicting.iter().find(|(_, set)| *set) {
+            return Err(invalid_path(&format!(
+                "it cannot be combined with `{}`",
+                annotation
+            )));
+        }
+    }
+
     Ok(FieldInfo {
         label,
         type_: type_str,
@@ -398,6 +874,15 @@ fn extract_field_info(
         custom_name: annotations.custom_name,
         decoder_with: annotations.decoder_with,
         encoder_with: annotations.encoder_with,
+        default: annotations.default,
+        skip_encoding: annotations.skip_encoding,
+        skip_decoding: annotations.skip_decoding,
+        absent_encoding: annotations.absent_encoding,
+        flatten: annotations.flatten,
+        aliases: annotations.aliases,
+        path: annotations.path,
+        extra: annotations.extra,
+        validation: annotations.validation,
     })
 }
 
@@ -405,9 +890,15 @@ fn extract_field_info(
 struct GlossAnnotations {
     encoders: Vec<EncoderType>,
     generate_decoder: bool,
+    decoder_backend: Option<String>,
     field_naming_strategy: Option<FieldNamingConvention>,
+    variant_naming_strategy: Option<FieldNamingConvention>,
     type_tag_field: Option<String>,
     disable_type_tag: bool,
+    representation: Option<EnumRepresentation>,
+    content_field: Option<String>,
+    int_enum: bool,
+    deny_unknown_fields: bool,
     output_override: Option<OutputOverride>,
     unknown_variant_message: Option<String>,
     fn_naming_override: Option<FnNamingOverride>,
@@ -423,9 +914,13 @@ fn parse_gloss_annotations(text: &str) -> GlossAnnotations {
         if let Some(args) = cap.get(1) {
             let args_str = args.as_str();
 
-            // Parse encoder(json)
-            if args_str.contains("encoder(json)") || args_str.contains("encoder(JSON)") {
-                annotations.encoders.push(EncoderType::Json);
+            // Parse encoder(json), encoder(msgpack), ...
+            let encoder_re = Regex::new(r"\bencoder\(\s*([A-Za-z_][A-Za-z0-9_]*)\s*\)").unwrap();
+            for cap in encoder_re.captures_iter(args_str) {
+                let encoder_type = EncoderType::from_name(&cap[1]);
+                if !annotations.encoders.contains(&encoder_type) {
+                    annotations.encoders.push(encoder_type);
+                }
             }
 
             // Parse decoder
@@ -433,12 +928,23 @@ fn parse_gloss_annotations(text: &str) -> GlossAnnotations {
                 annotations.generate_decoder = true;
             }
 
-            // Parse snake_case or camelCase
-            if args_str.contains("snake_case") {
-                annotations.field_naming_strategy = Some(FieldNamingConvention::SnakeCase);
+            // Parse decoder(toml), decoder(dynamic), ...
+            let decoder_re = Regex::new(r"\bdecoder\(\s*([A-Za-z_][A-Za-z0-9_]*)\s*\)").unwrap();
+            if let Some(cap) = decoder_re.captures(args_str) {
+                annotations.decoder_backend = Some(cap[1].to_string());
+            }
+
+            // Parse naming flags such as snake_case, camelCase or kebab-case
+            for flag in annotation_flags(args_str) {
+                if let Some(naming) = FieldNamingConvention::from_name(flag) {
+                    annotations.field_naming_strategy = Some(naming);
+                }
             }
-            if args_str.contains("camelCase") {
-                annotations.field_naming_strategy = Some(FieldNamingConvention::CamelCase);
+
+            // Parse variant_naming = "kebab-case"
+            let variant_naming_re = Regex::new(r#"variant_naming\s*=\s*"([^"]+)""#).unwrap();
+            if let Some(cap) = variant_naming_re.captures(args_str) {
+                annotations.variant_naming_strategy = FieldNamingConvention::from_name(&cap[1]);
             }
 
             // Parse type_tag = "field_name"
@@ -454,6 +960,28 @@ fn parse_gloss_annotations(text: &str) -> GlossAnnotations {
                 annotations.disable_type_tag = true;
             }
 
+            // Parse int_enum
+            if Regex::new(r"\bint_enum\b").unwrap().is_match(args_str) {
+                annotations.int_enum = true;
+            }
+
+            // Parse deny_unknown_fields
+            if Regex::new(r"\bdeny_unknown_fields\b").unwrap().is_match(args_str) {
+                annotations.deny_unknown_fields = true;
+            }
+
+            // Parse representation = "adjacent"
+            let representation_re = Regex::new(r#"representation\s*=\s*"([^"]+)""#).unwrap();
+            if let Some(cap) = representation_re.captures(args_str) {
+                annotations.representation = EnumRepresentation::from_name(&cap[1]);
+            }
+
+            // Parse content_field = "data"
+            let content_re = Regex::new(r#"content_field\s*=\s*"([^"]+)""#).unwrap();
+            if let Some(cap) = content_re.captures(args_str) {
+                annotations.content_field = Some(cap[1].to_string());
+            }
+
             // Parse output configuration overrides
             if let Some(output_override) = parse_output_override(args_str) {
                 annotations.output_override = Some(output_override);
@@ -472,12 +1000,75 @@ fn parse_gloss_annotations(text: &str) -> GlossAnnotations {
     annotations
 }
 
+/// Bare flags of an annotation line, i.e. the comma separated items without a value
+fn annotation_flags(args_str: &str) -> impl Iterator<Item = &str> {
+    args_str
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty() && !item.contains('=') && !item.contains('"'))
+}
+
+#[derive(Debug, Default)]
+struct VariantAnnotations {
+    tag: Option<String>,
+    skip: bool,
+    deprecated: bool,
+    fallback: bool,
+    value: Option<i64>,
+}
+
+fn parse_variant_annotations(text: &str) -> VariantAnnotations {
+    let mut annotations = VariantAnnotations::default();
+
+    let gloss_re = Regex::new(r"gloss!:\s*(.+)").unwrap();
+
+    for cap in gloss_re.captures_iter(text) {
+        if let Some(args) = cap.get(1) {
+            let args_str = args.as_str();
+
+            // Parse tag = "IMG" (but not type_tag = "...")
+            let tag_re = Regex::new(r#"(?:^|[\s,])tag\s*=\s*"([^"]+)""#).unwrap();
+            if let Some(tag_cap) = tag_re.captures(args_str) {
+                if let Some(tag) = tag_cap.get(1) {
+                    annotations.tag = Some(tag.as_str().to_string());
+                }
+            }
+
+            // Parse value = 3 (for int_enum types)
+            let value_re = Regex::new(r"(?:^|[\s,])value\s*=\s*(-?\d+)").unwrap();
+            if let Some(value_cap) = value_re.captures(args_str) {
+                annotations.value = value_cap[1].parse().ok();
+            }
+
+            for flag in annotation_flags(args_str) {
+                match flag {
+                    "skip" => annotations.skip = true,
+                    "deprecated" => annotations.deprecated = true,
+                    "fallback" => annotations.fallback = true,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    annotations
+}
+
 #[derive(Debug)]
 struct FieldAnnotations {
     marker: FieldMarker,
     custom_name: Option<String>,
     decoder_with: Option<String>,
     encoder_with: Option<String>,
+    default: Option<FieldDefault>,
+    skip_encoding: bool,
+    skip_decoding: bool,
+    absent_encoding: Option<AbsentEncoding>,
+    flatten: bool,
+    aliases: Vec<String>,
+    path: Vec<String>,
+    extra: bool,
+    validation: FieldValidation,
 }
 
 impl Default for FieldAnnotations {
@@ -487,6 +1078,15 @@ impl Default for FieldAnnotations {
             custom_name: None,
             decoder_with: None,
             encoder_with: None,
+            default: None,
+            skip_encoding: false,
+            skip_decoding: false,
+            absent_encoding: None,
+            flatten: false,
+            aliases: Vec::new(),
+            path: Vec::new(),
+            extra: false,
+            validation: FieldValidation::default(),
         }
     }
 }
@@ -534,12 +1134,133 @@ fn parse_field_annotations(text: &str) -> FieldAnnotations {
                     annotations.encoder_with = Some(value.as_str().to_string());
                 }
             }
+
+            // Parse default_fn = "module.function"
+            let default_fn_re = Regex::new(r#"default_fn\s*=\s*"([^"]+)""#).unwrap();
+            if let Some(cap) = default_fn_re.captures(args_str) {
+                if let Some(value) = cap.get(1) {
+                    annotations.default = Some(FieldDefault::Function(value.as_str().to_string()));
+                }
+            }
+
+            // Parse default = <gleam expression>
+            let default_re = Regex::new(r"(?:^|[\s,])default\s*=\s*").unwrap();
+            if let Some(m) = default_re.find(args_str) {
+                let expression = take_annotation_expression(&args_str[m.end()..]);
+                if !expression.is_empty() {
+                    annotations.default = Some(FieldDefault::Expression(expression));
+                }
+            }
+
+            // Parse aliases = ["oldName", "legacy_name"]
+            let aliases_re = Regex::new(r"aliases\s*=\s*\[([^\]]*)\]").unwrap();
+            if let Some(cap) = aliases_re.captures(args_str) {
+                let alias_re = Regex::new(r#""([^"]+)""#).unwrap();
+                annotations.aliases = alias_re
+                    .captures_iter(&cap[1])
+                    .map(|alias| alias[1].to_string())
+                    .collect();
+            }
+
+            // Parse path = "data.user.id"
+            let path_re = Regex::new(r#"(?:^|[\s,])path\s*=\s*"([^"]+)""#).unwrap();
+            if let Some(cap) = path_re.captures(args_str) {
+                annotations.path = cap[1].split('.').map(|key| key.to_string()).collect();
+            }
+
+            // Parse validation constraints: min = 0, max_length = 255, one_of = ["a", "b"], ...
+            let bound_re = Regex::new(r"(?:^|[\s,])(min|max)\s*=\s*(-?\d+(?:\.\d+)?)").unwrap();
+            for cap in bound_re.captures_iter(args_str) {
+                let bound = Some(cap[2].to_string());
+                match &cap[1] {
+                    "min" => annotations.validation.min = bound,
+                    _ => annotations.validation.max = bound,
+                }
+            }
+
+            let length_re = Regex::new(r"\b(min_length|max_length)\s*=\s*(\d+)").unwrap();
+            for cap in length_re.captures_iter(args_str) {
+                let length = cap[2].parse().ok();
+                match &cap[1] {
+                    "min_length" => annotations.validation.min_length = length,
+                    _ => annotations.validation.max_length = length,
+                }
+            }
+
+            let one_of_re = Regex::new(r"\bone_of\s*=\s*\[([^\]]*)\]").unwrap();
+            if let Some(cap) = one_of_re.captures(args_str) {
+                let value_re = Regex::new(r#""([^"]*)""#).unwrap();
+                annotations.validation.one_of = value_re
+                    .captures_iter(&cap[1])
+                    .map(|value| value[1].to_string())
+                    .collect();
+            }
+
+            let validate_with_re = Regex::new(r#"validate_with\s*=\s*"([^"]+)""#).unwrap();
+            if let Some(cap) = validate_with_re.captures(args_str) {
+                annotations.validation.validate_with = Some(cap[1].to_string());
+            }
+
+            // Parse absent_encoding = "null" | "omit"
+            let absent_re = Regex::new(r#"absent_encoding\s*=\s*"([^"]+)""#).unwrap();
+            if let Some(cap) = absent_re.captures(args_str) {
+                annotations.absent_encoding = AbsentEncoding::from_name(&cap[1]);
+            }
+
+            for flag in annotation_flags(args_str) {
+                match flag {
+                    "skip" => {
+                        annotations.skip_encoding = true;
+                        annotations.skip_decoding = true;
+                    }
+                    "skip_encoding" => annotations.skip_encoding = true,
+                    "skip_decoding" => annotations.skip_decoding = true,
+                    "flatten" => annotations.flatten = true,
+                    "extra" => annotations.extra = true,
+                    _ => {}
+                }
+            }
         }
     }
 
     annotations
 }
 
+/// Read a Gleam expression from an annotation, stopping at the first comma that is not
+/// nested inside brackets or a string literal.
+fn take_annotation_expression(input: &str) -> String {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut end = input.len();
+
+    for (index, ch) in input.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match ch {
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                end = index;
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    input[..end].trim().to_string()
+}
+
 /// Parse output configuration override from annotation string
 /// Handles: output_dir = "@/gen", separate_encoder_decoder = true, etc.
 fn parse_output_override(args_str: &str) -> Option<OutputOverride> {
@@ -683,6 +1404,7 @@ fn extract_comment_before(source: &str, position: usize) -> String {
 fn type_ast_to_expression(
     type_ast: &ast::TypeAst,
     option_availability: &OptionAvailability,
+    dict_availability: &DictAvailability,
 ) -> TypeExpression {
     match type_ast {
         ast::TypeAst::Constructor(c) => {
@@ -692,8 +1414,15 @@ fn type_ast_to_expression(
                     Some(alias) => option_availability.aliases.contains(alias),
                     None => option_availability.unqualified,
                 };
+            let is_standard_dict = c.name == "Dict"
+                && match &module_alias {
+                    Some(alias) => dict_availability.aliases.contains(alias),
+                    None => dict_availability.unqualified,
+                };
             let resolved_module = if is_standard_option {
                 Some("gleam/option".to_string())
+            } else if is_standard_dict {
+                Some("gleam/dict".to_string())
             } else {
                 module_alias.clone()
             };
@@ -704,29 +1433,74 @@ fn type_ast_to_expression(
                 arguments: c
                     .arguments
                     .iter()
-                    .map(|arg| type_ast_to_expression(arg, option_availability))
+                    .map(|arg| type_ast_to_expression(arg, option_availability, dict_availability))
                     .collect(),
             }
         }
         ast::TypeAst::Tuple(t) => TypeExpression::Tuple(
             t.elements
                 .iter()
-                .map(|elem| type_ast_to_expression(elem, option_availability))
+                .map(|elem| type_ast_to_expression(elem, option_availability, dict_availability))
                 .collect(),
         ),
         ast::TypeAst::Fn(f) => TypeExpression::Function {
             arguments: f
                 .arguments
                 .iter()
-                .map(|arg| type_ast_to_expression(arg, option_availability))
+                .map(|arg| type_ast_to_expression(arg, option_availability, dict_availability))
                 .collect(),
-            return_type: Box::new(type_ast_to_expression(&f.return_, option_availability)),
+            return_type: Box::new(type_ast_to_expression(
+                &f.return_,
+                option_availability,
+                dict_availability,
+            )),
         },
         ast::TypeAst::Var(v) => TypeExpression::Var(v.name.to_string()),
         ast::TypeAst::Hole { .. } => TypeExpression::Hole,
     }
 }
 
+/// Replace import aliases and unqualified imported names in `type_expr` with the full paths
+/// of the modules declaring the types, as given by the imports of the enclosing module
+fn resolve_type_modules(
+    type_expr: &TypeExpression,
+    module_imports: &BTreeMap<String, String>,
+    type_imports: &BTreeMap<String, String>,
+) -> TypeExpression {
+    let resolve = |expr: &TypeExpression| resolve_type_modules(expr, module_imports, type_imports);
+
+    match type_expr {
+        TypeExpression::Constructor {
+            module,
+            name,
+            arguments,
+        } => TypeExpression::Constructor {
+            module: match module {
+                Some(qualifier) => Some(
+                    module_imports
+                        .get(qualifier)
+                        .cloned()
+                        .unwrap_or_else(|| qualifier.clone()),
+                ),
+                None => type_imports.get(name).cloned(),
+            },
+            name: name.clone(),
+            arguments: arguments.iter().map(resolve).collect(),
+        },
+        TypeExpression::Tuple(elements) => {
+            TypeExpression::Tuple(elements.iter().map(resolve).collect())
+        }
+        TypeExpression::Function {
+            arguments,
+            return_type,
+        } => TypeExpression::Function {
+            arguments: arguments.iter().map(resolve).collect(),
+            return_type: Box::new(resolve(return_type)),
+        },
+        TypeExpression::Var(_) | TypeExpression::Hole => type_expr.clone(),
+    }
+}
+
 fn type_ast_to_string(type_ast: &ast::TypeAst) -> String {
     match type_ast {
         ast::TypeAst::Constructor(c) => {
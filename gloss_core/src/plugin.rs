@@ -0,0 +1,119 @@
+use crate::config::{BackendSettings, PluginConfig};
+use crate::parser::CustomTypeInfo;
+use crate::{GlossError, Result};
+use camino::Utf8PathBuf;
+use serde::Serialize;
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Message written to a plugin's stdin, one per annotated type
+#[derive(Debug, Serialize)]
+struct PluginRequest<'a> {
+    /// Name the plugin is registered under in `gloss.toml`
+    backend: &'a str,
+    /// Contents of the `[backend.<name>]` table, empty when there is none
+    settings: &'a BackendSettings,
+    /// Name the generated encoder function must have
+    function_name: &'a str,
+    #[serde(rename = "type")]
+    type_info: &'a CustomTypeInfo,
+}
+
+/// Run an external encoder plugin for a single type and return the Gleam code it printed
+pub(crate) fn run_plugin(
+    project_root: &Utf8PathBuf,
+    name: &str,
+    plugin: &PluginConfig,
+    settings: &BackendSettings,
+    function_name: &str,
+    type_info: &CustomTypeInfo,
+) -> Result<String> {
+    let request = serde_json::to_string(&PluginRequest {
+        backend: name,
+        settings,
+        function_name,
+        type_info,
+    })
+    .map_err(|error| {
+        GlossError::GenerationError(format!(
+            "Failed to serialize type `{}` for encoder plugin `{}`: {}",
+            type_info.name, name, error
+        ))
+    })?;
+
+    let program = if plugin.command.contains('/') {
+        project_root.join(&plugin.command)
+    } else {
+        Utf8PathBuf::from(&plugin.command)
+    };
+
+    let mut child = Command::new(program.as_std_path())
+        .args(&plugin.args)
+        .current_dir(project_root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| {
+            GlossError::GenerationError(format!(
+                "Failed to start encoder plugin `{}` (`{}`): {}",
+                name, plugin.command, error
+            ))
+        })?;
+
+    // Write the request while stdout and stderr are drained, so a plugin printing before it
+    // has read everything cannot block on a full pipe
+    let mut stdin = child.stdin.take();
+    let writer = thread::spawn(move || match stdin.as_mut() {
+        Some(stdin) => stdin.write_all(request.as_bytes()),
+        None => Ok(()),
+    });
+
+    let output = child.wait_with_output().map_err(|error| {
+        GlossError::GenerationError(format!(
+            "Failed to run encoder plugin `{}`: {}",
+            name, error
+        ))
+    })?;
+    let written = writer
+        .join()
+        .unwrap_or_else(|_| Err(ErrorKind::Other.into()));
+
+    // A plugin exiting without reading its input is reported by its status and stderr
+    if !output.status.success() {
+        return Err(GlossError::GenerationError(format!(
+            "Encoder plugin `{}` failed for type `{}` ({}): {}",
+            name,
+            type_info.name,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    if let Err(error) = written {
+        if error.kind() != ErrorKind::BrokenPipe {
+            return Err(GlossError::GenerationError(format!(
+                "Failed to send type `{}` to encoder plugin `{}`: {}",
+                type_info.name, name, error
+            )));
+        }
+    }
+
+    let code = String::from_utf8(output.stdout).map_err(|_| {
+        GlossError::GenerationError(format!(
+            "Encoder plugin `{}` printed invalid UTF-8 for type `{}`",
+            name, type_info.name
+        ))
+    })?;
+
+    let code = code.trim();
+    if code.is_empty() {
+        return Err(GlossError::GenerationError(format!(
+            "Encoder plugin `{}` printed no code for type `{}`",
+            name, type_info.name
+        )));
+    }
+
+    Ok(code.to_string())
+}
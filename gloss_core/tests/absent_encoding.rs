@@ -0,0 +1,78 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, gloss_toml: &str, source: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+    fs::write(root.join("gloss.toml"), gloss_toml).expect("write gloss.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    fs::write(src_dir.join("profile.gleam"), source).expect("write profile module");
+    src_dir.join("profile.gleam")
+}
+
+const PROFILE: &str = r#"
+import gleam/option.{type Option}
+
+// gloss!: encoder(json), decoder
+pub type Profile {
+  Profile(
+    id: String,
+    nickname: Option(String),
+    // gloss!: absent_encoding = "null"
+    bio: Option(String),
+    age: Int,
+  )
+}
+"#;
+
+#[test]
+fn omitted_none_fields_are_left_out_of_the_object() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let profile_path = write_project(&root, "absent_encoding = \"omit\"\n", PROFILE);
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&profile_path).expect("profile module generated");
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains("json.object(list.flatten(["));
+    assert!(encoder_code.contains(r#"[#("id", json.string(id))],"#));
+    assert!(encoder_code.contains(
+        r#"case nickname { option.Some(nickname) -> [#("nickname", json.string(nickname))] option.None -> [] },"#
+    ));
+    assert!(encoder_code.contains(
+        r#"[#("bio", json.nullable(bio, json.string)), #("age", json.int(age))]"#
+    ));
+    assert!(encoder_code.contains("import gleam/list"));
+    assert!(encoder_code.contains("import gleam/option"));
+}
+
+#[test]
+fn none_is_written_as_null_by_default() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let profile_path = write_project(&root, "", PROFILE);
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&profile_path).expect("profile module generated");
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"#("nickname", json.nullable(nickname, json.string))"#));
+    assert!(!encoder_code.contains("list.flatten"));
+}
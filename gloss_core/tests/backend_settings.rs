@@ -0,0 +1,136 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+const ENTRY_MODULE: &str = r#"
+// gloss!: encoder(json)
+pub type Entry {
+  Entry(id: Int)
+}
+"#;
+
+fn write_project(root: &Utf8PathBuf, gloss_toml: &str) {
+    // The replacement module comes from its own package, so no `gleam/json` dependency
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"#,
+    )
+    .expect("write gleam manifest");
+    fs::write(root.join("gloss.toml"), gloss_toml).expect("write gloss config");
+    fs::create_dir_all(root.join("src")).expect("create src directory");
+}
+
+#[test]
+fn json_backend_settings_cascade_per_key() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    write_project(
+        &root,
+        r#"
+[backend.json]
+module = "thoas/json"
+alias = "j"
+return_type = "Value"
+"#,
+    );
+
+    let admin_dir = root.join("src/admin");
+    fs::create_dir_all(&admin_dir).expect("create admin directory");
+    fs::write(
+        admin_dir.join("gloss.toml"),
+        "[backend.json]\nalias = \"aj\"\n",
+    )
+    .expect("write admin config");
+
+    let entry_path = root.join("src/entry.gleam");
+    let admin_path = admin_dir.join("entry.gleam");
+    fs::write(&entry_path, ENTRY_MODULE).expect("write entry module");
+    fs::write(&admin_path, ENTRY_MODULE).expect("write admin module");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+
+    let encoder_code = generated.get(&entry_path).expect("entry module generated")[0]
+        .get_encoder_code(true, false);
+    assert!(encoder_code.contains("import thoas/json as j\n"));
+    assert!(encoder_code.contains("pub fn entry_to_json(entry: Entry) -> j.Value"));
+    assert!(encoder_code.contains(r#"#("id", j.int(id))"#));
+    assert!(!encoder_code.contains("gleam/json"));
+
+    let admin_code = generated.get(&admin_path).expect("admin module generated")[0]
+        .get_encoder_code(true, false);
+    assert!(admin_code.contains("import thoas/json as aj\n"));
+    assert!(admin_code.contains("pub fn entry_to_json(entry: Entry) -> aj.Value"));
+    assert!(admin_code.contains(r#"#("id", aj.int(id))"#));
+}
+
+#[test]
+fn unknown_backend_settings_are_rejected() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    write_project(
+        &root,
+        "[backend.json]\nmodule = \"thoas/json\"\nindent = \"2\"\n",
+    );
+    fs::write(root.join("src/entry.gleam"), ENTRY_MODULE).expect("write entry module");
+
+    let registry = BackendRegistry::new();
+    let error = generate_for_project(&root, &registry).expect_err("unknown setting");
+    assert!(error.to_string().contains(
+        "Unknown setting `indent` for the json backend. Expected one of: alias, module, return_type"
+    ));
+}
+
+#[test]
+fn extra_values_are_converted_with_the_configured_module() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    write_project(
+        &root,
+        r#"
+[backend.json]
+module = "thoas/json"
+alias = "j"
+return_type = "Value"
+"#,
+    );
+
+    let entry_path = root.join("src/entry.gleam");
+    fs::write(
+        &entry_path,
+        r#"
+import gleam/dict.{type Dict}
+import gleam/dynamic.{type Dynamic}
+
+// gloss!: encoder(json)
+pub type Entry {
+  Entry(
+    id: Int,
+    // gloss!: extra
+    rest: Dict(String, Dynamic),
+  )
+}
+"#,
+    )
+    .expect("write entry module");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+
+    let encoder_code = generated.get(&entry_path).expect("entry module generated")[0]
+        .get_encoder_code(true, false);
+    assert!(encoder_code.contains("import thoas/json as j\n"));
+    assert!(encoder_code.contains("fn entry_extra_value_to_json(value: dynamic.Dynamic) -> j.Value {"));
+    assert!(encoder_code.contains("decode.map(decode.string, j.string)"));
+    assert!(encoder_code.contains("j.preprocessed_array"));
+    assert!(encoder_code.contains("Error(_) -> j.null()"));
+    assert!(!encoder_code.contains("gleam/json"));
+    assert!(!encoder_code.contains("json.Json"));
+}
@@ -0,0 +1,179 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry, EncoderBackend, JsonEncoderBackend};
+use std::sync::Arc;
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, gloss_toml: Option<&str>) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam manifest");
+    if let Some(config) = gloss_toml {
+        fs::write(root.join("gloss.toml"), config).expect("write gloss config");
+    }
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src directory");
+    fs::write(
+        src_dir.join("blob.gleam"),
+        r#"
+// gloss!: encoder(json)
+pub type Blob {
+  Blob(data: BitArray, chunks: List(BitArray), marker: Nil)
+}
+"#,
+    )
+    .expect("write module");
+    src_dir.join("blob.gleam")
+}
+
+#[test]
+fn bit_arrays_are_encoded_as_base64_by_default() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let blob_path = write_project(&root, None);
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&blob_path).expect("blob module generated");
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains("import gleam/bit_array"));
+    assert!(encoder_code.contains(r#"#("data", json.string(bit_array.base64_encode(data, True)))"#));
+    assert!(encoder_code.contains(
+        "json.array(chunks, fn(value) { json.string(bit_array.base64_encode(value, True)) })"
+    ));
+    assert!(encoder_code.contains(r#"#("marker", json.null())"#));
+}
+
+#[test]
+fn hex_encoding_can_be_configured() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let blob_path = write_project(&root, Some("bit_array_encoding = \"hex\"\n"));
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&blob_path).expect("blob module generated");
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"#("data", json.string(bit_array.base16_encode(data)))"#));
+    assert!(!encoder_code.contains("base64_encode"));
+}
+
+#[test]
+fn hex_encoding_survives_subdirectory_configs() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let blob_path = write_project(&root, Some("bit_array_encoding = \"hex\"\n"));
+    fs::write(
+        root.join("src/gloss.toml"),
+        "absent_field_mode = \"maybe_absent\"\n",
+    )
+    .expect("write subdirectory config");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&blob_path).expect("blob module generated");
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"#("data", json.string(bit_array.base16_encode(data)))"#));
+}
+
+#[test]
+fn binary_backends_do_not_import_bit_array() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let blob_path = write_project(&root, None);
+
+    let registry = BackendRegistry::new().with_backend(
+        "json",
+        Arc::new(BinaryBackend::default()) as gloss_core::EncoderBackendRef,
+    );
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&blob_path).expect("blob module generated");
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"#("data", json.bits(data))"#));
+    assert!(!encoder_code.contains("bit_array"));
+}
+
+/// The json backend, except that bit arrays are written natively
+#[derive(Default)]
+struct BinaryBackend(JsonEncoderBackend);
+
+impl EncoderBackend for BinaryBackend {
+    fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    fn module_imports(&self) -> Vec<String> {
+        self.0.module_imports()
+    }
+
+    fn return_type(&self) -> String {
+        self.0.return_type()
+    }
+
+    fn encode_object(
+        &self,
+        indent: &str,
+        fields: &[(String, String)],
+        closing_indent: &str,
+    ) -> String {
+        self.0.encode_object(indent, fields, closing_indent)
+    }
+
+    fn encode_empty_object(&self, indent: &str) -> String {
+        self.0.encode_empty_object(indent)
+    }
+
+    fn encode_string_literal(&self, value: &str) -> String {
+        self.0.encode_string_literal(value)
+    }
+
+    fn encode_string(&self, value_expr: &str) -> String {
+        self.0.encode_string(value_expr)
+    }
+
+    fn encode_int(&self, value_expr: &str) -> String {
+        self.0.encode_int(value_expr)
+    }
+
+    fn encode_float(&self, value_expr: &str) -> String {
+        self.0.encode_float(value_expr)
+    }
+
+    fn encode_bool(&self, value_expr: &str) -> String {
+        self.0.encode_bool(value_expr)
+    }
+
+    fn encode_nullable(&self, value_expr: &str, inner_encoder: &str) -> String {
+        self.0.encode_nullable(value_expr, inner_encoder)
+    }
+
+    fn encode_array(&self, value_expr: &str, inner_encoder: &str) -> String {
+        self.0.encode_array(value_expr, inner_encoder)
+    }
+
+    fn encode_nil(&self) -> Option<String> {
+        self.0.encode_nil()
+    }
+
+    fn encode_bit_array(&self, value_expr: &str, _text_expr: &str) -> Option<String> {
+        Some(format!("json.bits({})", value_expr))
+    }
+
+    fn bit_array_text_form(&self) -> bool {
+        false
+    }
+}
@@ -231,3 +231,60 @@ pub type Leader {
     );
     assert_eq!(leader_group.path_mode, PathMode::FileRelative);
 }
+
+#[test]
+fn subdirectory_configs_keep_unset_settings_from_the_root() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+
+    write_gleam_manifest(&root);
+
+    fs::write(
+        root.join("gloss.toml"),
+        r#"
+absent_encoding = "omit"
+variant_naming_strategy = "camel_case"
+enum_representation = "adjacent"
+deny_unknown_fields = true
+"#,
+    )
+    .expect("write global gloss.toml");
+
+    let api_dir = root.join("src/api");
+    fs::create_dir_all(&api_dir).expect("create api dir");
+    fs::write(
+        api_dir.join("gloss.toml"),
+        r#"
+[output]
+directory = "@/api_gen"
+"#,
+    )
+    .expect("write api gloss.toml");
+
+    let shape_path = api_dir.join("shape.gleam");
+    fs::write(
+        &shape_path,
+        r#"
+import gleam/option.{type Option}
+
+// gloss!: encoder(json), decoder
+pub type Shape {
+  BigCircle(radius: Int, label: Option(String))
+  Square(side: Int)
+}
+"#,
+    )
+    .expect("write shape file");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&shape_path).expect("shape module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#""bigCircle" -> decode.at(["data"], {"#));
+    assert!(decoder_code.contains(r#"!list.contains(["radius", "label"], key)"#));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"#("type", json.string("bigCircle"))"#));
+    assert!(encoder_code.contains("option.None -> []"));
+}
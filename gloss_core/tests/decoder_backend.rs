@@ -0,0 +1,228 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry, DecoderBackend};
+use std::sync::Arc;
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, source: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam manifest");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src directory");
+    fs::write(src_dir.join("entry.gleam"), source).expect("write module");
+    src_dir.join("entry.gleam")
+}
+
+#[test]
+fn custom_decoder_backend_is_used() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let entry_path = write_project(
+        &root,
+        r#"
+import gleam/option.{type Option}
+
+// gloss!: decoder(strings)
+pub type Entry {
+  Entry(
+    id: Int,
+    name: String,
+    tags: List(String),
+    // gloss!: maybe_absent
+    note: Option(String),
+  )
+}
+
+// gloss!: decoder
+pub type Plain {
+  Plain(id: Int)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new().with_decoder_backend(
+        "strings",
+        Arc::new(StringMapBackend) as gloss_core::DecoderBackendRef,
+    );
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&entry_path).expect("entry module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains("import app/strmap as sm"));
+    assert!(decoder_code.contains("import gleam/dynamic/decode"));
+    assert!(decoder_code.contains("pub fn entry_decoder() -> sm.Decoder(Entry)"));
+    assert!(decoder_code.contains(r#"use id <- sm.field("id", sm.int)"#));
+    assert!(decoder_code.contains(r#"use tags <- sm.field("tags", sm.list(sm.string))"#));
+    assert!(decoder_code
+        .contains(r#"use note <- sm.optional_field("note", option.None, sm.optional(sm.string))"#));
+    assert!(decoder_code.contains("sm.success(Entry(id:, name:, tags:, note:))"));
+    assert!(decoder_code.contains("pub fn plain_decoder() -> decode.Decoder(Plain)"));
+}
+
+#[test]
+fn unknown_decoder_backends_are_reported() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    write_project(
+        &root,
+        r#"
+// gloss!: decoder(toml)
+pub type Entry {
+  Entry(id: Int)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let error = generate_for_project(&root, &registry).expect_err("unknown backend");
+    assert!(error.to_string().contains(
+        "No decoder backend registered for `decoder(toml)`. Registered backends: dynamic"
+    ));
+}
+
+#[test]
+fn decoders_of_other_backends_are_rejected() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    write_project(
+        &root,
+        r#"
+// gloss!: decoder(strings)
+pub type Entry {
+  Entry(owner: Owner)
+}
+
+// gloss!: decoder
+pub type Owner {
+  Owner(id: Int)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new().with_decoder_backend(
+        "strings",
+        Arc::new(StringMapBackend) as gloss_core::DecoderBackendRef,
+    );
+    let error = generate_for_project(&root, &registry).expect_err("mixed backends");
+    assert!(error
+        .to_string()
+        .contains("Decoder for type `Owner` is generated with backend `dynamic`"));
+}
+
+/// Decodes from string maps through a hypothetical `app/strmap` module
+struct StringMapBackend;
+
+impl DecoderBackend for StringMapBackend {
+    fn name(&self) -> &str {
+        "strings"
+    }
+
+    fn module_imports(&self) -> Vec<String> {
+        vec!["import app/strmap as sm".to_string()]
+    }
+
+    fn decoder_type(&self, inner: &str) -> String {
+        format!("sm.Decoder({})", inner)
+    }
+
+    fn field(&self, binding: &str, key: &str, decoder: &str) -> String {
+        format!(r#"use {} <- sm.field("{}", {})"#, binding, key, decoder)
+    }
+
+    fn optional_field(
+        &self,
+        binding: &str,
+        key: &str,
+        default_expr: &str,
+        decoder: &str,
+    ) -> String {
+        format!(
+            r#"use {} <- sm.optional_field("{}", {}, {})"#,
+            binding, key, default_expr, decoder
+        )
+    }
+
+    fn subfield(&self, binding: &str, path: &[String], decoder: &str) -> String {
+        format!("use {} <- sm.then({})", binding, self.at(path, decoder))
+    }
+
+    fn then(&self, binding: &str, decoder: &str) -> String {
+        format!("use {} <- sm.then({})", binding, decoder)
+    }
+
+    fn at(&self, path: &[String], decoder: &str) -> String {
+        format!(r#"sm.at("{}", {})"#, path.join("."), decoder)
+    }
+
+    fn optionally_at(&self, path: &[String], default_expr: &str, decoder: &str) -> String {
+        format!(
+            r#"sm.optionally_at("{}", {}, {})"#,
+            path.join("."),
+            default_expr,
+            decoder
+        )
+    }
+
+    fn element(&self, index: usize, decoder: &str) -> String {
+        format!(r#"sm.at("{}", {})"#, index, decoder)
+    }
+
+    fn success(&self, value_expr: &str) -> String {
+        format!("sm.success({})", value_expr)
+    }
+
+    fn failure(&self, default_expr: &str, message_expr: &str) -> String {
+        format!("sm.failure({}, {})", default_expr, message_expr)
+    }
+
+    fn and_then(&self, decoder: &str, next_fn: &str) -> String {
+        format!("sm.then({}, {})", decoder, next_fn)
+    }
+
+    fn map(&self, decoder: &str, map_fn: &str) -> String {
+        format!("sm.map({}, {})", decoder, map_fn)
+    }
+
+    fn one_of(&self, first: &str, alternatives: &[String]) -> String {
+        format!("sm.one_of({}, [{}])", first, alternatives.join(", "))
+    }
+
+    fn recursive(&self, decoder: &str) -> String {
+        format!("sm.lazy(fn() {{ {} }})", decoder)
+    }
+
+    fn string(&self) -> String {
+        "sm.string".to_string()
+    }
+
+    fn int(&self) -> String {
+        "sm.int".to_string()
+    }
+
+    fn float(&self) -> String {
+        "sm.float".to_string()
+    }
+
+    fn bool(&self) -> String {
+        "sm.bool".to_string()
+    }
+
+    fn optional(&self, inner_decoder: &str) -> String {
+        format!("sm.optional({})", inner_decoder)
+    }
+
+    fn list(&self, inner_decoder: &str) -> String {
+        format!("sm.list({})", inner_decoder)
+    }
+}
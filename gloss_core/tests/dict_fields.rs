@@ -0,0 +1,110 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry, GlossError};
+use tempfile::tempdir;
+
+fn write_gleam_manifest(root: &Utf8PathBuf) {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+}
+
+#[test]
+fn string_keyed_dicts_are_decoded_and_encoded() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+
+    write_gleam_manifest(&root);
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+
+    fs::write(
+        src_dir.join("profile.gleam"),
+        r#"
+// gloss!: encoder(json), decoder
+pub type Profile {
+  Profile(name: String)
+}
+"#,
+    )
+    .expect("write profile module");
+
+    fs::write(
+        src_dir.join("scores.gleam"),
+        r#"
+import gleam/dict.{type Dict}
+import profile
+
+// gloss!: encoder(json), decoder
+pub type Scores {
+  Scores(
+    totals: Dict(String, Int),
+    profiles: dict.Dict(String, profile.Profile),
+  )
+}
+"#,
+    )
+    .expect("write scores module");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+
+    let scores_path = src_dir.join("scores.gleam");
+    let groups = generated.get(&scores_path).expect("scores module generated");
+    assert_eq!(groups.len(), 1);
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains("decode.dict(decode.string, decode.int)"));
+    assert!(decoder_code.contains("decode.dict(decode.string, profile.profile_decoder())"));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains("json.dict(totals, fn(key) { key }, json.int)"));
+    assert!(encoder_code.contains("json.dict(profiles, fn(key) { key }, profile.profile_to_json)"));
+}
+
+#[test]
+fn non_string_dict_keys_are_rejected() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+
+    write_gleam_manifest(&root);
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+
+    fs::write(
+        src_dir.join("lookup.gleam"),
+        r#"
+import gleam/dict.{type Dict}
+
+// gloss!: decoder
+pub type Lookup {
+  Lookup(by_id: Dict(Int, String))
+}
+"#,
+    )
+    .expect("write lookup module");
+
+    let registry = BackendRegistry::new();
+    let error = generate_for_project(&root, &registry).expect_err("expected key type error");
+
+    match error {
+        GlossError::GenerationError(message) => {
+            assert!(
+                message.contains("Dictionary keys must be `String`") && message.contains("`Int`"),
+                "unexpected message: {message}"
+            );
+        }
+        other => panic!("unexpected error kind: {other:?}"),
+    }
+}
@@ -57,6 +57,196 @@ pub type Entry {
     assert!(decoder_code.contains("decode.success"));
 }
 
+#[test]
+fn named_backends_sit_next_to_json() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam manifest");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src directory");
+
+    fs::write(
+        src_dir.join("entry.gleam"),
+        r#"
+// gloss!: encoder(json), encoder(custom)
+pub type Entry {
+  Entry(id: Int, name: String)
+}
+"#,
+    )
+    .expect("write module");
+
+    let registry = BackendRegistry::new().with_backend(
+        "custom",
+        Arc::new(CustomJsonBackend) as gloss_core::EncoderBackendRef,
+    );
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+
+    let entry_path = src_dir.join("entry.gleam");
+    let groups = generated.get(&entry_path).expect("entry module generated");
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains("pub fn entry_to_json(entry: Entry) -> json.Json"));
+    assert!(encoder_code.contains("pub fn entry_to_custom(entry: Entry) -> cj.Value"));
+    assert!(encoder_code.contains("import gleam/json"));
+    assert!(encoder_code.contains("import custom/json as cj"));
+}
+
+#[test]
+fn unknown_backend_names_are_reported() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam manifest");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src directory");
+
+    fs::write(
+        src_dir.join("entry.gleam"),
+        r#"
+// gloss!: encoder(yaml)
+pub type Entry {
+  Entry(id: Int)
+}
+"#,
+    )
+    .expect("write module");
+
+    let registry = BackendRegistry::new().with_backend(
+        EncoderType::from_name("custom"),
+        Arc::new(CustomJsonBackend) as gloss_core::EncoderBackendRef,
+    );
+    let error = generate_for_project(&root, &registry).expect_err("unknown backend");
+    assert!(error.to_string().contains(
+        "No encoder backend registered for `encoder(yaml)`. Registered backends: custom, json"
+    ));
+}
+
+#[test]
+fn constructs_without_backend_hooks_are_reported() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam manifest");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src directory");
+
+    fs::write(
+        src_dir.join("entry.gleam"),
+        r#"
+// gloss!: encoder(custom)
+pub type Entry {
+  Entry(id: Int, payload: BitArray)
+}
+"#,
+    )
+    .expect("write module");
+
+    let registry = BackendRegistry::new().with_backend(
+        "custom",
+        Arc::new(CustomJsonBackend) as gloss_core::EncoderBackendRef,
+    );
+    let error = generate_for_project(&root, &registry).expect_err("unsupported bit array");
+    assert!(error
+        .to_string()
+        .contains("Encoder backend `custom-json` does not support bit arrays"));
+}
+
+#[test]
+fn extra_values_need_a_gleam_json_backend() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam manifest");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src directory");
+
+    let registry = BackendRegistry::new().with_backend(
+        "custom",
+        Arc::new(CustomJsonBackend) as gloss_core::EncoderBackendRef,
+    );
+    for (value_type, message) in [
+        (
+            "json.Json",
+            "Field `rest` holds `json.Json` values, which only encoders built on `gleam/json` can write",
+        ),
+        (
+            "dynamic.Dynamic",
+            "Field `rest` holds `Dynamic` values, which only encoders built on the `gleam/json` API can encode",
+        ),
+    ] {
+        fs::write(
+            src_dir.join("entry.gleam"),
+            format!(
+                r#"
+import gleam/dict.{{type Dict}}
+import gleam/dynamic
+import gleam/json
+
+// gloss!: encoder(custom)
+pub type Entry {{
+  Entry(
+    id: Int,
+    // gloss!: extra
+    rest: Dict(String, {}),
+  )
+}}
+"#,
+                value_type
+            ),
+        )
+        .expect("write module");
+
+        let error = generate_for_project(&root, &registry).expect_err("unsupported extra values");
+        assert!(error.to_string().contains(message));
+    }
+}
+
 struct CustomJsonBackend;
 
 impl EncoderBackend for CustomJsonBackend {
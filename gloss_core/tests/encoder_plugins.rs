@@ -0,0 +1,125 @@
+#![cfg(unix)]
+
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, plugin_script: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam manifest");
+
+    fs::write(
+        root.join("gloss.toml"),
+        r#"[backends.yaml]
+command = "sh"
+args = ["tools/plugin.sh", "Node"]
+imports = ["my/yaml"]
+"#,
+    )
+    .expect("write gloss config");
+
+    fs::create_dir_all(root.join("tools")).expect("create tools directory");
+    fs::write(root.join("tools/plugin.sh"), plugin_script).expect("write plugin");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src directory");
+    fs::write(
+        src_dir.join("entry.gleam"),
+        r#"
+// gloss!: encoder(json), encoder(yaml)
+pub type Entry {
+  Entry(id: Int)
+}
+"#,
+    )
+    .expect("write module");
+    src_dir.join("entry.gleam")
+}
+
+#[test]
+fn plugin_output_is_written_next_to_builtin_encoders() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let entry_path = write_project(
+        &root,
+        r#"input=$(cat)
+name=$(printf '%s' "$input" | sed -n 's/.*"function_name":"\([^"]*\)".*/\1/p')
+label=$(printf '%s' "$input" | sed -n 's/.*"label":"\([^"]*\)".*/\1/p')
+printf 'pub fn %s(value: Entry) -> yaml.%s {\n  yaml.map([#("%s", yaml.int(value.%s))])\n}\n' "$name" "$1" "$label" "$label"
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&entry_path).expect("entry module generated");
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains("import my/yaml"));
+    assert!(encoder_code.contains("import gleam/json"));
+    assert!(encoder_code.contains("pub fn entry_to_json(entry: Entry) -> json.Json"));
+    assert!(encoder_code.contains("pub fn entry_to_yaml(value: Entry) -> yaml.Node"));
+    assert!(encoder_code.contains(r#"yaml.map([#("id", yaml.int(value.id))])"#));
+}
+
+#[test]
+fn failing_plugins_are_reported() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    // The plugin exits without reading its input
+    write_project(&root, "echo 'unsupported field type' >&2\nexit 3\n");
+
+    let registry = BackendRegistry::new();
+    let error = generate_for_project(&root, &registry).expect_err("plugin failure");
+    let message = error.to_string();
+    assert!(message.contains("Encoder plugin `yaml` failed for type `Entry`"));
+    assert!(message.contains("unsupported field type"));
+}
+
+#[test]
+fn plugins_receive_full_module_paths_of_imported_types() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let entry_path = write_project(
+        &root,
+        r#"input=$(cat)
+module=$(printf '%s' "$input" | sed -n 's/.*"module":"\([^"]*\)".*/\1/p')
+printf 'pub fn entry_to_yaml(value: Entry) -> yaml.Node {\n  owner_to_yaml(value.owner, "%s")\n}\n' "$module"
+"#,
+    );
+    fs::create_dir_all(root.join("src/models")).expect("create models directory");
+    fs::write(
+        root.join("src/models/user.gleam"),
+        "pub type User {\n  User(name: String)\n}\n",
+    )
+    .expect("write models module");
+    fs::write(
+        &entry_path,
+        r#"
+import models/user.{type User}
+
+// gloss!: encoder(yaml)
+pub type Entry {
+  Entry(owner: User)
+}
+"#,
+    )
+    .expect("write module");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&entry_path).expect("entry module generated");
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"owner_to_yaml(value.owner, "models/user")"#));
+}
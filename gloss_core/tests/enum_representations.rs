@@ -0,0 +1,119 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, gloss_toml: Option<&str>, source: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    if let Some(config) = gloss_toml {
+        fs::write(root.join("gloss.toml"), config).expect("write gloss.toml");
+    }
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    fs::write(src_dir.join("content.gleam"), source).expect("write content module");
+    src_dir.join("content.gleam")
+}
+
+#[test]
+fn adjacent_representation_from_annotation() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let content_path = write_project(
+        &root,
+        None,
+        r#"
+// gloss!: encoder(json), decoder, representation = "adjacent", type_tag = "kind", content_field = "payload"
+pub type Content {
+  Image(url: String)
+  Empty
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&content_path).expect("content module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#"use variant <- decode.field("kind", decode.string)"#));
+    assert!(decoder_code.contains(r#""image" -> decode.at(["payload"], {"#));
+    assert!(decoder_code.contains(r#""empty" -> {"#));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"#("kind", json.string("image"))"#));
+    assert!(encoder_code.contains(r#"#("payload", json.object(["#));
+    assert!(encoder_code.contains(r#"#("kind", json.string("empty"))"#));
+}
+
+#[test]
+fn external_representation_from_config() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let content_path = write_project(
+        &root,
+        Some("enum_representation = \"external\"\n"),
+        r#"
+// gloss!: encoder(json), decoder
+pub type Content {
+  Image(url: String)
+  Text(body: String)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&content_path).expect("content module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#"decode.one_of(decode.at(["image"], {"#));
+    assert!(decoder_code.contains("}), [\n    decode.at([\"text\"], {"));
+    assert!(!decoder_code.contains("use variant"));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"#("image", json.object(["#));
+    assert!(encoder_code.contains(r#"#("text", json.object(["#));
+}
+
+#[test]
+fn untagged_representation_tries_each_variant() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let content_path = write_project(
+        &root,
+        None,
+        r#"
+// gloss!: encoder(json), decoder, representation = "untagged"
+pub type Content {
+  Image(url: String)
+  Text(body: String)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&content_path).expect("content module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains("decode.one_of({"));
+    assert!(decoder_code.contains(r#"use url <- decode.field("url", decode.string)"#));
+    assert!(decoder_code.contains(r#"use body <- decode.field("body", decode.string)"#));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(!encoder_code.contains(r#"#("type""#));
+    assert!(encoder_code.contains(r#"#("url", json.string(url))"#));
+}
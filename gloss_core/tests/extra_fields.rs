@@ -0,0 +1,131 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, source: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    fs::write(src_dir.join("event.gleam"), source).expect("write event module");
+    src_dir.join("event.gleam")
+}
+
+#[test]
+fn dynamic_extras_round_trip_unknown_keys() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let event_path = write_project(
+        &root,
+        r#"
+import gleam/dict.{type Dict}
+import gleam/dynamic.{type Dynamic}
+
+// gloss!: encoder(json), decoder, deny_unknown_fields
+pub type Event {
+  Event(
+    id: String,
+    // gloss!: rename = "occurredAt"
+    occurred_at: Int,
+    // gloss!: extra
+    rest: Dict(String, Dynamic),
+  )
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&event_path).expect("event module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(
+        r#"use rest <- decode.then(decode.map(decode.dict(decode.string, decode.dynamic), fn(object) { dict.drop(object, ["id", "occurredAt"]) }))"#
+    ));
+    assert!(!decoder_code.contains("list.contains"));
+    assert!(decoder_code.contains("decode.success(Event(id:, occurred_at:, rest:))"));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"[#("id", json.string(id)), #("occurredAt", json.int(occurred_at))]"#));
+    assert!(encoder_code.contains(
+        "list.map(dict.to_list(rest), fn(entry) { #(entry.0, event_extra_value_to_json(entry.1)) })"
+    ));
+    assert!(encoder_code.contains("fn event_extra_value_to_json(value: dynamic.Dynamic) -> json.Json {"));
+    assert!(!encoder_code.contains(r#""rest""#));
+}
+
+#[test]
+fn json_extras_are_converted_while_decoding() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let event_path = write_project(
+        &root,
+        r#"
+import gleam/dict.{type Dict}
+import gleam/json
+
+// gloss!: encoder(json), decoder
+pub type Event {
+  Event(
+    id: String,
+    // gloss!: extra
+    rest: Dict(String, json.Json),
+  )
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&event_path).expect("event module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(
+        "decode.dict(decode.string, decode.map(decode.dynamic, event_extra_value_to_json))"
+    ));
+    assert!(decoder_code.contains("fn event_extra_value_to_json("));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains("dict.to_list(rest)"));
+    assert!(!encoder_code.contains("fn event_extra_value_to_json("));
+}
+
+#[test]
+fn extra_values_must_come_from_gleam_dynamic_or_gleam_json() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    write_project(
+        &root,
+        r#"
+import gleam/dict.{type Dict}
+import app/types.{type Dynamic}
+
+// gloss!: decoder
+pub type Event {
+  Event(
+    id: String,
+    // gloss!: extra
+    rest: Dict(String, Dynamic),
+  )
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let error = generate_for_project(&root, &registry).expect_err("foreign Dynamic type");
+    assert!(error
+        .to_string()
+        .contains("Field `rest` is marked `extra` but has type `Dict(String, Dynamic)`"));
+}
@@ -0,0 +1,145 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, source: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    fs::write(src_dir.join("event.gleam"), source).expect("write event module");
+    src_dir.join("event.gleam")
+}
+
+#[test]
+fn unknown_tags_decode_into_the_fallback_variant() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let event_path = write_project(
+        &root,
+        r#"
+// gloss!: encoder(json), decoder
+pub type Event {
+  Created(id: String)
+  Deleted(id: String)
+  // gloss!: fallback
+  Unknown(tag: String)
+}
+
+// gloss!: encoder(json), decoder
+pub type Level {
+  Low
+  High
+  // gloss!: fallback
+  Other(name: String)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&event_path).expect("event module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains("_ -> decode.success(Unknown(tag: variant))"));
+    assert!(decoder_code.contains("_ -> decode.success(Other(name: variant))"));
+    assert!(!decoder_code.contains(r#""unknown" -> "#));
+    assert!(!decoder_code.contains("decode.failure("));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains("Unknown(tag:) -> json.object(["));
+    assert!(encoder_code.contains(r#"#("type", json.string(tag))"#));
+    assert!(encoder_code.contains("Other(name:) -> json.string(name)"));
+    assert!(encoder_code.contains(r#"High -> json.string("high")"#));
+}
+
+#[test]
+fn untagged_fallback_keeps_the_raw_input() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let event_path = write_project(
+        &root,
+        r#"
+import gleam/dynamic.{type Dynamic}
+
+// gloss!: encoder(json), decoder, representation = "external"
+pub type Event {
+  Created(id: String)
+  // gloss!: fallback
+  Unknown(raw: Dynamic)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&event_path).expect("event module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains("decode.map(decode.dynamic, fn(value) { Unknown(raw: value) })"));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains("Unknown(raw:) -> event_extra_value_to_json(raw)"));
+    assert!(encoder_code.contains("fn event_extra_value_to_json(value: dynamic.Dynamic)"));
+}
+
+#[test]
+fn fallback_tags_need_a_tagged_representation() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    write_project(
+        &root,
+        r#"
+// gloss!: encoder(json), decoder, representation = "untagged"
+pub type Event {
+  Created(id: String)
+  // gloss!: fallback
+  Unknown(tag: String)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let error = generate_for_project(&root, &registry).expect_err("untagged fallback tag");
+    assert!(error
+        .to_string()
+        .contains("Fallback variant `Unknown` of type `Event` keeps the unrecognised tag"));
+}
+
+#[test]
+fn fallback_inside_a_tag_value_is_not_a_flag() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let event_path = write_project(
+        &root,
+        r#"
+// gloss!: encoder(json), decoder
+pub type Event {
+  Created(id: String)
+  // gloss!: tag = "fallback"
+  Reverted(id: String)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&event_path).expect("event module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#""fallback" -> {"#));
+    assert!(decoder_code.contains("decode.failure("));
+    assert!(!decoder_code.contains("_ -> decode.success(Reverted"));
+}
@@ -0,0 +1,100 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, source: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    fs::write(src_dir.join("user.gleam"), source).expect("write user module");
+    src_dir.join("user.gleam")
+}
+
+#[test]
+fn aliases_are_tried_after_the_primary_key() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let user_path = write_project(
+        &root,
+        r#"
+import gleam/option.{type Option}
+
+// gloss!: encoder(json), decoder
+pub type User {
+  User(
+    // gloss!: rename = "userId", aliases = ["user_id", "uid"]
+    id: String,
+    // gloss!: aliases = ["nick"], maybe_absent
+    nickname: Option(String),
+    // gloss!: aliases = ["tries"], default = 3
+    retries: Int,
+  )
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&user_path).expect("user module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(
+        r#"use id <- decode.then(decode.then(decode.optionally_at(["userId"], False, decode.success(True)), fn(present) { case present { True -> decode.at(["userId"], decode.string) False -> decode.then(decode.optionally_at(["user_id"], False, decode.success(True)), fn(present) { case present { True -> decode.at(["user_id"], decode.string) False -> decode.then(decode.optionally_at(["uid"], False, decode.success(True)), fn(present) { case present { True -> decode.at(["uid"], decode.string) False -> decode.at(["userId"], decode.string) } }) } }) } }))"#
+    ));
+    assert!(decoder_code.contains(
+        r#"use nickname <- decode.then(decode.then(decode.optionally_at(["nickname"], False, decode.success(True)), fn(present) { case present { True -> decode.at(["nickname"], decode.optional(decode.string)) False -> decode.then(decode.optionally_at(["nick"], False, decode.success(True)), fn(present) { case present { True -> decode.at(["nick"], decode.optional(decode.string)) False -> decode.success(option.None) } }) } }))"#
+    ));
+    assert!(decoder_code.contains(
+        r#"use retries <- decode.then(decode.then(decode.optionally_at(["retries"], False, decode.success(True)), fn(present) { case present { True -> decode.at(["retries"], decode.int) False -> decode.then(decode.optionally_at(["tries"], False, decode.success(True)), fn(present) { case present { True -> decode.at(["tries"], decode.int) False -> decode.success(3) } }) } }))"#
+    ));
+    assert!(!decoder_code.contains("decode.one_of("));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"#("userId", json.string(id))"#));
+    assert!(!encoder_code.contains("user_id"));
+    assert!(!encoder_code.contains("nick\""));
+}
+
+#[test]
+fn invalid_primary_values_do_not_fall_back_to_aliases() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let user_path = write_project(
+        &root,
+        r#"
+// gloss!: decoder
+pub type User {
+  User(
+    // gloss!: aliases = ["tries"], min = 0, default = 3
+    retries: Int,
+  )
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&user_path).expect("user module generated");
+
+    // A present `retries` key is decoded and checked on its own; the alias and the
+    // default are only reached when the key is missing
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(
+        r#"True -> decode.at(["retries"], decode.then(decode.int, fn(value) { case value >= 0 { True -> decode.success(value) False -> decode.failure(value, "a number at least 0") } })) False -> decode.then(decode.optionally_at(["tries"], False, decode.success(True))"#
+    ));
+    assert!(decoder_code.contains("False -> decode.success(3) }"));
+    assert!(!decoder_code.contains("decode.one_of("));
+}
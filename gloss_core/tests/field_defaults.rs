@@ -0,0 +1,75 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, source: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    fs::write(
+        src_dir.join("settings.gleam"),
+        "pub fn default_name() -> String {\n  \"worker\"\n}\n",
+    )
+    .expect("write settings module");
+    fs::write(src_dir.join("job.gleam"), source).expect("write job module");
+    src_dir.join("job.gleam")
+}
+
+#[test]
+fn defaults_are_used_for_missing_keys() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let job_path = write_project(
+        &root,
+        r#"
+import gleam/dict.{type Dict} as d
+
+// gloss!: decoder
+pub type Job {
+  Job(
+    // gloss!: default = 3
+    retries: Int,
+    // gloss!: default = [], rename = "jobTags"
+    tags: List(String),
+    // gloss!: default = d.new()
+    labels: Dict(String, String),
+    // gloss!: default_fn = "settings.default_name"
+    name: String,
+    // gloss!: default = "a, \"b\""
+    note: String,
+    id: String,
+  )
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&job_path).expect("job module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#"use retries <- decode.optional_field("retries", 3, decode.int)"#));
+    assert!(decoder_code
+        .contains(r#"use tags <- decode.optional_field("jobTags", [], decode.list(decode.string))"#));
+    assert!(decoder_code.contains(r#"decode.optional_field("labels", dict.new(), "#));
+    assert!(decoder_code
+        .contains(r#"use name <- decode.optional_field("name", settings.default_name(), decode.string)"#));
+    assert!(decoder_code.contains(r#"decode.optional_field("note", "a, \"b\"", decode.string)"#));
+    assert!(decoder_code.contains(r#"use id <- decode.field("id", decode.string)"#));
+    assert!(decoder_code.contains("import gleam/dict\n"));
+    assert!(decoder_code.contains("import settings"));
+    assert_eq!(decoder_code.matches("import gleam/dict\n").count(), 1);
+}
@@ -0,0 +1,103 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, source: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    fs::write(src_dir.join("signup.gleam"), source).expect("write signup module");
+    src_dir.join("signup.gleam")
+}
+
+#[test]
+fn constraints_are_checked_after_decoding() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let signup_path = write_project(
+        &root,
+        r#"
+import gleam/option.{type Option}
+
+// gloss!: decoder
+pub type Signup {
+  Signup(
+    // gloss!: min = 0, max = 150
+    age: Int,
+    // gloss!: min = 0
+    score: Float,
+    // gloss!: min_length = 1, max_length = 32
+    name: String,
+    // gloss!: max_length = 5
+    tags: List(String),
+    // gloss!: one_of = ["free", "pro"]
+    plan: String,
+    // gloss!: validate_with = "checks.email"
+    email: String,
+    // gloss!: min_length = 2
+    nickname: Option(String),
+  )
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&signup_path).expect("signup module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(
+        r#"use age <- decode.field("age", decode.then(decode.then(decode.int, fn(value) { case value >= 0 { True -> decode.success(value) False -> decode.failure(value, "a number at least 0") } }), fn(value) { case value <= 150 { True -> decode.success(value) False -> decode.failure(value, "a number at most 150") } }))"#
+    ));
+    assert!(decoder_code.contains("case value >=. 0.0 {"));
+    assert!(decoder_code.contains("case string.length(value) >= 1 {"));
+    assert!(decoder_code.contains(r#"decode.failure(value, "a length of at most 32")"#));
+    assert!(decoder_code.contains("case list.length(value) <= 5 {"));
+    assert!(decoder_code.contains(r#"case list.contains(["free", "pro"], value) {"#));
+    assert!(decoder_code.contains(r#"decode.failure(value, "one of free, pro")"#));
+    assert!(decoder_code.contains(
+        "decode.then(decode.string, fn(value) { case checks.email(value) { Ok(value) -> decode.success(value) Error(message) -> decode.failure(value, message) } })"
+    ));
+    assert!(decoder_code.contains(
+        r#"use nickname <- decode.field("nickname", decode.optional(decode.then(decode.string, fn(value) { case string.length(value) >= 2 {"#
+    ));
+    assert!(decoder_code.contains("import checks"));
+    assert!(decoder_code.contains("import gleam/string"));
+}
+
+#[test]
+fn constraints_must_match_the_field_type() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    write_project(
+        &root,
+        r#"
+// gloss!: decoder
+pub type Signup {
+  Signup(
+    // gloss!: min_length = 1
+    age: Int,
+  )
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let error = generate_for_project(&root, &registry).expect_err("unsupported constraint");
+    assert!(error
+        .to_string()
+        .contains("`min_length` cannot be used on field `age` of type `Int`"));
+}
@@ -0,0 +1,151 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, metadata: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    fs::write(src_dir.join("metadata.gleam"), metadata).expect("write metadata module");
+    fs::write(
+        src_dir.join("profile.gleam"),
+        r#"
+import metadata
+
+// gloss!: encoder(json), decoder
+pub type Profile {
+  Profile(
+    id: String,
+    // gloss!: flatten
+    meta: metadata.Metadata,
+  )
+}
+"#,
+    )
+    .expect("write profile module");
+    src_dir.join("profile.gleam")
+}
+
+#[test]
+fn flattened_records_share_the_parent_object() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let profile_path = write_project(
+        &root,
+        r#"
+// gloss!: decoder, camel_case
+pub type Metadata {
+  Metadata(created_by: String, version: Int)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&profile_path).expect("profile module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#"use id <- decode.field("id", decode.string)"#));
+    assert!(decoder_code.contains("use meta <- decode.then({"));
+    assert!(decoder_code.contains(r#"use created_by <- decode.field("createdBy", decode.string)"#));
+    assert!(decoder_code.contains("decode.success(metadata.Metadata(created_by:, version:))"));
+    assert!(decoder_code.contains("decode.success(Profile(id:, meta:))"));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"#("id", json.string(id))"#));
+    assert!(encoder_code.contains(r#"#("createdBy", json.string(meta.created_by))"#));
+    assert!(encoder_code.contains(r#"#("version", json.int(meta.version))"#));
+    assert!(!encoder_code.contains(r#"#("meta""#));
+}
+
+#[test]
+fn flattened_key_collisions_are_rejected() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    write_project(
+        &root,
+        r#"
+// gloss!: decoder
+pub type Metadata {
+  Metadata(id: String, version: Int)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let error = generate_for_project(&root, &registry).expect_err("colliding keys");
+    assert!(error.to_string().contains(
+        r#"Key "id" is used by both field `id` of `Profile` and field `id` of `Metadata`"#
+    ));
+}
+
+#[test]
+fn flattened_records_from_other_modules_qualify_their_encoders() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let profile_path = write_project(
+        &root,
+        r#"
+// gloss!: encoder(json), decoder
+pub type Tag {
+  Tag(label: String)
+}
+
+// gloss!: encoder(json), decoder
+pub type Metadata {
+  Metadata(
+    tag: Tag,
+    // gloss!: decoder_with = "stamp_decoder", encoder_with = "stamp_to_json"
+    stamp: Int,
+  )
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&profile_path).expect("profile module generated");
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"#("tag", metadata.tag_to_json(meta.tag))"#));
+    assert!(encoder_code.contains(r#"#("stamp", metadata.stamp_to_json(meta.stamp))"#));
+    assert!(encoder_code.contains("import metadata"));
+}
+
+#[test]
+fn flattened_records_leave_unknown_keys_to_the_parent() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let profile_path = write_project(
+        &root,
+        r#"
+// gloss!: decoder, camel_case
+pub type Metadata {
+  Metadata(created_by: String, version: Int)
+}
+"#,
+    );
+    fs::write(root.join("gloss.toml"), "deny_unknown_fields = true\n").expect("write gloss.toml");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&profile_path).expect("profile module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#"!list.contains(["id", "createdBy", "version"], key)"#));
+    assert_eq!(decoder_code.matches("list.contains(").count(), 1);
+    assert!(!decoder_code.contains("metadata_decoder()"));
+}
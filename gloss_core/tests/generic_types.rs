@@ -0,0 +1,123 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+#[test]
+fn generic_types_take_codecs_for_their_parameters() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+
+    fs::write(
+        src_dir.join("page.gleam"),
+        r#"
+// gloss!: encoder(json), decoder
+pub type Page(item) {
+  Page(items: List(item), total: Int)
+}
+"#,
+    )
+    .expect("write page module");
+
+    fs::write(
+        src_dir.join("user.gleam"),
+        r#"
+import page
+
+// gloss!: encoder(json), decoder
+pub type User {
+  User(name: String)
+}
+
+// gloss!: encoder(json), decoder
+pub type Listing {
+  Listing(users: page.Page(User))
+}
+"#,
+    )
+    .expect("write user module");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+
+    let page_path = src_dir.join("page.gleam");
+    let page_groups = generated.get(&page_path).expect("page module generated");
+    let page_decoder = page_groups[0].get_decoder_code(true, false);
+    assert!(page_decoder.contains(
+        "pub fn page_decoder(item_decoder: decode.Decoder(item)) -> decode.Decoder(Page(item))"
+    ));
+    assert!(page_decoder.contains(r#"use items <- decode.field("items", decode.list(item_decoder))"#));
+
+    let page_encoder = page_groups[0].get_encoder_code(true, false);
+    assert!(page_encoder.contains(
+        "pub fn page_to_json(page: Page(item), item_encoder: fn(item) -> json.Json) -> json.Json"
+    ));
+    assert!(page_encoder.contains("json.array(items, item_encoder)"));
+
+    let user_path = src_dir.join("user.gleam");
+    let user_groups = generated.get(&user_path).expect("user module generated");
+    let listing_decoder = user_groups[0].get_decoder_code(true, false);
+    assert!(listing_decoder.contains("page.page_decoder(user_decoder())"));
+
+    let listing_encoder = user_groups[0].get_encoder_code(true, false);
+    assert!(listing_encoder.contains("page.page_to_json(users, user_to_json)"));
+}
+
+#[test]
+fn generic_type_argument_count_is_checked() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+
+    fs::write(
+        src_dir.join("pair.gleam"),
+        r#"
+// gloss!: decoder
+pub type Pair(a, b) {
+  Pair(first: a, second: b)
+}
+
+// gloss!: decoder
+pub type Holder {
+  Holder(pair: Pair(Int))
+}
+"#,
+    )
+    .expect("write pair module");
+
+    let registry = BackendRegistry::new();
+    let error = generate_for_project(&root, &registry).expect_err("argument count mismatch");
+    assert!(error
+        .to_string()
+        .contains("Type `Pair` expects 2 type argument(s) but 1 were given"));
+}
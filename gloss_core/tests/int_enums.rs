@@ -0,0 +1,81 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, source: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    fs::write(src_dir.join("status.gleam"), source).expect("write status module");
+    src_dir.join("status.gleam")
+}
+
+#[test]
+fn int_enums_use_integer_values() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let status_path = write_project(
+        &root,
+        r#"
+// gloss!: encoder(json), decoder, int_enum
+pub type Status {
+  Pending
+  // gloss!: value = 10
+  Active
+  Closed
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&status_path).expect("status module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains("use variant <- decode.then(decode.int)"));
+    assert!(decoder_code.contains("0 -> decode.success(Pending)"));
+    assert!(decoder_code.contains("10 -> decode.success(Active)"));
+    assert!(decoder_code.contains("11 -> decode.success(Closed)"));
+    assert!(decoder_code.contains(r#"_ -> decode.failure(Pending, "one of 0, 10, 11")"#));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains("Pending -> json.int(0)"));
+    assert!(encoder_code.contains("Active -> json.int(10)"));
+    assert!(encoder_code.contains("Closed -> json.int(11)"));
+}
+
+#[test]
+fn int_enums_reject_duplicate_values() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    write_project(
+        &root,
+        r#"
+// gloss!: decoder, int_enum
+pub type Status {
+  Pending
+  // gloss!: value = 0
+  Active
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let error = generate_for_project(&root, &registry).expect_err("duplicate values");
+    assert!(error
+        .to_string()
+        .contains("Variants `Pending` and `Active` of type `Status` both use the value 0"));
+}
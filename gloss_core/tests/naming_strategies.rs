@@ -0,0 +1,103 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, gloss_toml: Option<&str>, source: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    if let Some(config) = gloss_toml {
+        fs::write(root.join("gloss.toml"), config).expect("write gloss.toml");
+    }
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    fs::write(src_dir.join("request.gleam"), source).expect("write request module");
+    src_dir.join("request.gleam")
+}
+
+#[test]
+fn field_naming_flags_on_types() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let request_path = write_project(
+        &root,
+        None,
+        r#"
+// gloss!: encoder(json), decoder, kebab-case
+pub type Headers {
+  Headers(content_type: String, x_request_id: String)
+}
+
+// gloss!: encoder(json), decoder, PascalCase
+pub type DotNet {
+  DotNet(user_name: String)
+}
+
+// gloss!: encoder(json), decoder, SCREAMING_SNAKE_CASE
+pub type Env {
+  Env(home_dir: String)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&request_path).expect("request module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#"decode.field("content-type", decode.string)"#));
+    assert!(decoder_code.contains(r#"decode.field("x-request-id", decode.string)"#));
+    assert!(decoder_code.contains(r#"decode.field("UserName", decode.string)"#));
+    assert!(decoder_code.contains(r#"decode.field("HOME_DIR", decode.string)"#));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"#("content-type", json.string(content_type))"#));
+    assert!(encoder_code.contains(r#"#("UserName", json.string(user_name))"#));
+}
+
+#[test]
+fn variant_naming_strategy_from_config_and_annotation() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let request_path = write_project(
+        &root,
+        Some("variant_naming_strategy = \"screaming_snake_case\"\n"),
+        r#"
+// gloss!: encoder(json), decoder
+pub type Method {
+  HttpGet
+  HttpPost
+}
+
+// gloss!: encoder(json), decoder, variant_naming = "kebab-case"
+pub type Event {
+  UserCreated(id: Int)
+  UserDeleted(id: Int)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&request_path).expect("request module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#""HTTP_GET" -> {"#));
+    assert!(decoder_code.contains(r#""user-created" -> {"#));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"json.string("HTTP_POST")"#));
+    assert!(encoder_code.contains(r#"json.string("user-deleted")"#));
+}
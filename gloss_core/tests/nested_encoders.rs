@@ -0,0 +1,74 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_gleam_manifest(root: &Utf8PathBuf) {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+}
+
+#[test]
+fn option_and_list_fields_use_real_inner_encoders() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+
+    write_gleam_manifest(&root);
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+
+    fs::write(
+        src_dir.join("item.gleam"),
+        r#"
+// gloss!: encoder(json)
+pub type Item {
+  Item(id: Int)
+}
+"#,
+    )
+    .expect("write item module");
+
+    fs::write(
+        src_dir.join("basket.gleam"),
+        r#"
+import gleam/option.{type Option}
+import item
+
+// gloss!: encoder(json)
+pub type Basket {
+  Basket(
+    note: Option(String),
+    tags: List(String),
+    items: List(Option(List(item.Item))),
+  )
+}
+"#,
+    )
+    .expect("write basket module");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+
+    let basket_path = src_dir.join("basket.gleam");
+    let groups = generated.get(&basket_path).expect("basket module generated");
+    assert_eq!(groups.len(), 1);
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains("json.nullable(note, json.string)"));
+    assert!(encoder_code.contains("json.array(tags, json.string)"));
+    assert!(encoder_code.contains(
+        "json.array(items, fn(value) { json.nullable(value, fn(value) { json.array(value, item.item_to_json) }) })"
+    ));
+    assert!(!encoder_code.contains(", _)"));
+}
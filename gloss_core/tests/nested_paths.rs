@@ -0,0 +1,157 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, source: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    fs::write(src_dir.join("event.gleam"), source).expect("write event module");
+    src_dir.join("event.gleam")
+}
+
+#[test]
+fn path_fields_read_and_write_nested_objects() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let event_path = write_project(
+        &root,
+        r#"
+import gleam/option.{type Option}
+
+// gloss!: encoder(json), decoder
+pub type Event {
+  Event(
+    kind: String,
+    // gloss!: path = "data.user.id"
+    user_id: String,
+    // gloss!: path = "data.user.name", default = "anonymous"
+    user_name: String,
+    // gloss!: path = "data.score"
+    score: Option(Int),
+  )
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&event_path).expect("event module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#"use kind <- decode.field("kind", decode.string)"#));
+    assert!(decoder_code
+        .contains(r#"use user_id <- decode.subfield(["data", "user", "id"], decode.string)"#));
+    assert!(decoder_code.contains(
+        r#"use user_name <- decode.then(decode.optionally_at(["data", "user", "name"], "anonymous", decode.string))"#
+    ));
+    assert!(decoder_code.contains(
+        r#"use score <- decode.then(decode.optionally_at(["data", "score"], option.None, decode.optional(decode.int)))"#
+    ));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert_eq!(
+        encoder_code.matches(r#"#("data", json.object(["#).count(),
+        1
+    );
+    assert_eq!(
+        encoder_code.matches(r#"#("user", json.object(["#).count(),
+        1
+    );
+    assert!(encoder_code.contains(r#"#("id", json.string(user_id))"#));
+    assert!(encoder_code.contains(r#"#("name", json.string(user_name))"#));
+    assert!(encoder_code.contains(r#"#("score", json.nullable(score, json.int))"#));
+}
+
+#[test]
+fn paths_through_other_keys_are_rejected() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    write_project(
+        &root,
+        r#"
+// gloss!: encoder(json), decoder
+pub type Event {
+  Event(
+    data: String,
+    // gloss!: path = "data.id"
+    id: String,
+  )
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let error = generate_for_project(&root, &registry).expect_err("overlapping keys");
+    assert!(error.to_string().contains(
+        "Key \"data\" is used by both field `data` of `Event` and field `id` of `Event`"
+    ));
+}
+
+#[test]
+fn malformed_paths_are_rejected() {
+    let cases = [
+        (
+            r#"path = "data..id""#,
+            "keys between the dots cannot be empty",
+        ),
+        (r#"path = ".id""#, "keys between the dots cannot be empty"),
+        (r#"path = "data.""#, "keys between the dots cannot be empty"),
+        (
+            r#"path = "data.id", rename = "identifier""#,
+            "it cannot be combined with `rename`",
+        ),
+        (
+            r#"path = "data.id", flatten"#,
+            "it cannot be combined with `flatten`",
+        ),
+        (
+            r#"path = "data.id", extra"#,
+            "it cannot be combined with `extra`",
+        ),
+    ];
+
+    for (annotation, reason) in cases {
+        let temp = tempdir().expect("temp dir");
+        let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+        write_project(
+            &root,
+            &format!(
+                r#"
+// gloss!: encoder(json), decoder
+pub type Event {{
+  Event(
+    // gloss!: {}
+    id: String,
+  )
+}}
+"#,
+                annotation
+            ),
+        );
+
+        let registry = BackendRegistry::new();
+        let error = generate_for_project(&root, &registry).expect_err("invalid path");
+        assert!(
+            error
+                .to_string()
+                .contains(&format!("Field `id` has an invalid `path`: {}", reason)),
+            "{}: {}",
+            annotation,
+            error
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    src_dir
+}
+
+#[test]
+fn self_recursive_types_decode_lazily() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let src_dir = write_project(&root);
+
+    fs::write(
+        src_dir.join("tree.gleam"),
+        r#"
+// gloss!: encoder(json), decoder
+pub type Node {
+  Node(name: String, children: List(Node))
+}
+
+// gloss!: encoder(json), decoder
+pub type Expr {
+  Add(left: Expr, right: Expr)
+  Num(value: Int)
+}
+"#,
+    )
+    .expect("write tree module");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+
+    let tree_path = src_dir.join("tree.gleam");
+    let groups = generated.get(&tree_path).expect("tree module generated");
+    let decoder_code = groups[0].get_decoder_code(true, false);
+
+    assert!(decoder_code.contains(
+        r#"use children <- decode.field("children", decode.list(decode.recursive(fn() { node_decoder() })))"#
+    ));
+    assert!(decoder_code.contains(
+        r#"use left <- decode.field("left", decode.recursive(fn() { expr_decoder() }))"#
+    ));
+    // The failure default skips the self-referencing variant
+    assert!(decoder_code.contains("decode.failure(Num(value: 0)"));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains("json.array(children, node_to_json)"));
+}
+
+#[test]
+fn mutually_recursive_types_decode_lazily() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let src_dir = write_project(&root);
+
+    fs::write(
+        src_dir.join("thread.gleam"),
+        r#"
+import gleam/option.{type Option}
+
+// gloss!: decoder
+pub type Post {
+  Post(title: String, author: Author, comments: List(Comment))
+}
+
+// gloss!: decoder
+pub type Comment {
+  Comment(body: String, quoted: Option(Post))
+}
+
+// gloss!: decoder
+pub type Author {
+  Author(name: String)
+}
+"#,
+    )
+    .expect("write thread module");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+
+    let groups = generated
+        .get(&src_dir.join("thread.gleam"))
+        .expect("thread module generated");
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains("decode.list(decode.recursive(fn() { comment_decoder() }))"));
+    assert!(decoder_code.contains("decode.optional(decode.recursive(fn() { post_decoder() }))"));
+    assert!(decoder_code.contains(r#"use author <- decode.field("author", author_decoder())"#));
+}
@@ -0,0 +1,90 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, source: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    fs::write(src_dir.join("account.gleam"), source).expect("write account module");
+    src_dir.join("account.gleam")
+}
+
+#[test]
+fn skipped_fields_are_left_out_per_direction() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let account_path = write_project(
+        &root,
+        r#"
+// gloss!: encoder(json), decoder
+pub type Account {
+  Account(
+    id: String,
+    // gloss!: skip_decoding
+    created_at: Int,
+    // gloss!: skip_encoding
+    password: String,
+    // gloss!: skip, default = ["cached"]
+    cache: List(String),
+  )
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&account_path).expect("account module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#"use id <- decode.field("id", decode.string)"#));
+    assert!(decoder_code.contains("let created_at = 0"));
+    assert!(decoder_code.contains(r#"use password <- decode.field("password", decode.string)"#));
+    assert!(decoder_code.contains(r#"let cache = ["cached"]"#));
+    assert!(!decoder_code.contains(r#""created_at""#));
+    assert!(decoder_code.contains("decode.success(Account(id:, created_at:, password:, cache:))"));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains("let Account(id:, created_at:, ..) = account"));
+    assert!(encoder_code.contains(r#"#("created_at", json.int(created_at))"#));
+    assert!(!encoder_code.contains("password"));
+    assert!(!encoder_code.contains("cache"));
+}
+
+#[test]
+fn skipped_fields_without_default_are_rejected() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    write_project(
+        &root,
+        r#"
+// gloss!: decoder
+pub type Account {
+  Account(
+    id: String,
+    // gloss!: skip_decoding
+    on_change: fn(String) -> Nil,
+  )
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let error = generate_for_project(&root, &registry).expect_err("missing default");
+    assert!(error
+        .to_string()
+        .contains("Field `on_change` is skipped during decoding but has no default value"));
+}
@@ -0,0 +1,59 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+#[test]
+fn tuple_fields_are_positional_arrays() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+
+    fs::write(
+        src_dir.join("place.gleam"),
+        r#"
+// gloss!: encoder(json), decoder
+pub type Place {
+  Place(coords: #(Float, Float), score: #(String, Int))
+}
+"#,
+    )
+    .expect("write place module");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+
+    let place_path = src_dir.join("place.gleam");
+    let groups = generated.get(&place_path).expect("place module generated");
+    assert_eq!(groups.len(), 1);
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(
+        r#"use _ <- decode.then(decode.then(decode.list(decode.dynamic), fn(elements) { case list.length(elements) == 2 { True -> decode.success(Nil) False -> decode.failure(Nil, "expected array of length 2") } }))"#
+    ));
+    assert!(decoder_code.contains("import gleam/list"));
+    assert!(decoder_code.contains("use element_0 <- decode.then(decode.at([0], decode.float))"));
+    assert!(decoder_code.contains("use element_1 <- decode.then(decode.at([1], decode.int))"));
+    assert!(decoder_code.contains("decode.success(#(element_0, element_1))"));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code
+        .contains("json.preprocessed_array([json.float(coords.0), json.float(coords.1)])"));
+    assert!(encoder_code
+        .contains("json.preprocessed_array([json.string(score.0), json.int(score.1)])"));
+}
@@ -0,0 +1,225 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+#[test]
+fn type_aliases_are_expanded_before_generation() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(src_dir.join("shared")).expect("create src dir");
+
+    fs::write(
+        src_dir.join("shared/ids.gleam"),
+        r#"
+import gleam/option.{type Option}
+
+pub type UserId =
+  String
+
+pub type Maybe(a) =
+  Option(a)
+
+// gloss!: encoder(json), decoder
+pub type Tag {
+  Tag(label: String)
+}
+
+pub type Tags =
+  List(Tag)
+"#,
+    )
+    .expect("write ids module");
+
+    fs::write(
+        src_dir.join("user.gleam"),
+        r#"
+import shared/ids
+
+pub type Nickname =
+  ids.Maybe(String)
+
+// gloss!: encoder(json), decoder
+pub type User {
+  User(id: ids.UserId, nickname: Nickname, tags: ids.Tags)
+}
+"#,
+    )
+    .expect("write user module");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+
+    let user_path = src_dir.join("user.gleam");
+    let groups = generated.get(&user_path).expect("user module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#"use id <- decode.field("id", decode.string)"#));
+    assert!(decoder_code
+        .contains(r#"use nickname <- decode.field("nickname", decode.optional(decode.string))"#));
+    assert!(decoder_code.contains(r#"use tags <- decode.field("tags", decode.list(ids.tag_decoder()))"#));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains("json.string(id)"));
+    assert!(encoder_code.contains("json.nullable(nickname, json.string)"));
+    assert!(encoder_code.contains("json.array(tags, ids.tag_to_json)"));
+}
+
+#[test]
+fn alias_targets_resolve_against_the_alias_module_imports() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(src_dir.join("shared")).expect("create src dir");
+
+    fs::write(
+        src_dir.join("shared/tags.gleam"),
+        r#"
+// gloss!: encoder(json), decoder
+pub type Tag {
+  Tag(label: String)
+}
+"#,
+    )
+    .expect("write tags module");
+
+    fs::write(
+        src_dir.join("types.gleam"),
+        r#"
+import gleam/option.{type Option}
+import shared/tags as t
+
+pub type UserId =
+  String
+
+pub type MaybeName =
+  Option(String)
+
+pub type Labels =
+  List(t.Tag)
+"#,
+    )
+    .expect("write types module");
+
+    fs::write(
+        src_dir.join("account.gleam"),
+        r#"
+import types.{type MaybeName, type UserId}
+
+// gloss!: encoder(json), decoder
+pub type Account {
+  Account(id: UserId, name: MaybeName, labels: types.Labels)
+}
+"#,
+    )
+    .expect("write account module");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+
+    let account_path = src_dir.join("account.gleam");
+    let groups = generated.get(&account_path).expect("account module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#"use id <- decode.field("id", decode.string)"#));
+    assert!(decoder_code
+        .contains(r#"use name <- decode.field("name", decode.optional(decode.string))"#));
+    assert!(decoder_code
+        .contains(r#"use labels <- decode.field("labels", decode.list(tags.tag_decoder()))"#));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains("json.string(id)"));
+    assert!(encoder_code.contains("json.nullable(name, json.string)"));
+    assert!(encoder_code.contains("json.array(labels, tags.tag_to_json)"));
+}
+
+#[test]
+fn imported_types_resolve_to_the_same_module_however_they_are_named() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(src_dir.join("shared")).expect("create src dir");
+
+    fs::write(
+        src_dir.join("shared/tags.gleam"),
+        r#"
+// gloss!: encoder(json), decoder
+pub type Tag {
+  Tag(label: String)
+}
+"#,
+    )
+    .expect("write tags module");
+
+    fs::write(
+        src_dir.join("account.gleam"),
+        r#"
+import shared/tags.{type Tag}
+
+// gloss!: encoder(json), decoder
+pub type Account {
+  Account(
+    primary: Tag,
+    // gloss!: skip_decoding
+    backup: tags.Tag,
+  )
+}
+"#,
+    )
+    .expect("write account module");
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+
+    let account_path = src_dir.join("account.gleam");
+    let groups = generated.get(&account_path).expect("account module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#"use primary <- decode.field("primary", tags.tag_decoder())"#));
+    assert!(decoder_code.contains(r#"let backup = tags.Tag(label: "")"#));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"#("primary", tags.tag_to_json(primary))"#));
+    assert!(encoder_code.contains(r#"#("backup", tags.tag_to_json(backup))"#));
+}
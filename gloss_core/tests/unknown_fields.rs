@@ -0,0 +1,109 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, gloss_toml: &str, source: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+    fs::write(root.join("gloss.toml"), gloss_toml).expect("write gloss.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    fs::write(src_dir.join("shape.gleam"), source).expect("write shape module");
+    src_dir.join("shape.gleam")
+}
+
+#[test]
+fn strict_types_reject_unknown_keys() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let shape_path = write_project(
+        &root,
+        "",
+        r#"
+// gloss!: decoder, deny_unknown_fields
+pub type Shape {
+  Circle(radius: Float)
+  // gloss!: tag = "rect"
+  Rectangle(
+    width: Float,
+    // gloss!: rename = "h", aliases = ["height"]
+    height: Float,
+  )
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&shape_path).expect("shape module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code
+        .contains("use _ <- decode.then(decode.then(decode.dict(decode.string, decode.dynamic), fn(object) {"));
+    assert!(decoder_code.contains(r#"!list.contains(["type", "radius"], key)"#));
+    assert!(decoder_code.contains(r#"!list.contains(["type", "width", "h", "height"], key)"#));
+    assert!(decoder_code.contains(
+        r#"unknown -> decode.failure(Nil, "known fields only (unexpected: " <> string.join(unknown, ", ") <> ")")"#
+    ));
+    assert!(decoder_code.contains("import gleam/dict"));
+    assert!(decoder_code.contains("import gleam/list"));
+    assert!(decoder_code.contains("import gleam/string"));
+}
+
+#[test]
+fn strictness_can_be_enabled_project_wide() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let shape_path = write_project(
+        &root,
+        "deny_unknown_fields = true\n",
+        r#"
+// gloss!: decoder
+pub type Point {
+  Point(x: Int, y: Int)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&shape_path).expect("shape module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#"!list.contains(["x", "y"], key)"#));
+}
+
+#[test]
+fn decoders_ignore_unknown_keys_by_default() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let shape_path = write_project(
+        &root,
+        "",
+        r#"
+// gloss!: decoder
+pub type Point {
+  Point(x: Int, y: Int)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&shape_path).expect("shape module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(!decoder_code.contains("decode.dynamic"));
+}
@@ -0,0 +1,121 @@
+use std::fs;
+
+use camino::Utf8PathBuf;
+use gloss_core::{generate_for_project, BackendRegistry, GlossError};
+use tempfile::tempdir;
+
+fn write_project(root: &Utf8PathBuf, source: &str) -> Utf8PathBuf {
+    fs::write(
+        root.join("gleam.toml"),
+        r#"[project]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+"gleam/json" = "~> 1.0"
+"#,
+    )
+    .expect("write gleam.toml");
+
+    let src_dir = root.join("src");
+    fs::create_dir_all(&src_dir).expect("create src dir");
+    fs::write(src_dir.join("media.gleam"), source).expect("write media module");
+    src_dir.join("media.gleam")
+}
+
+#[test]
+fn variant_annotations_control_tags() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let media_path = write_project(
+        &root,
+        r#"
+// gloss!: encoder(json), decoder
+pub type Media {
+  // gloss!: tag = "IMG"
+  Image(url: String)
+  // gloss!: tag = "text/plain"
+  Text(body: String)
+  // gloss!: tag = "v2-video", deprecated
+  Video(url: String)
+  // gloss!: skip
+  Draft(notes: String)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&media_path).expect("media module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#""IMG" -> {"#));
+    assert!(decoder_code.contains(r#""text/plain" -> {"#));
+    assert!(decoder_code.contains(r#""v2-video" -> {"#));
+    assert!(!decoder_code.contains(r#""draft" -> {"#));
+    assert!(decoder_code.contains(r#""one of IMG, text/plain")"#));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"#("type", json.string("IMG"))"#));
+    assert!(encoder_code.contains(r#"#("type", json.string("v2-video"))"#));
+    assert!(encoder_code.contains(r#"#("type", json.string("draft"))"#));
+    assert!(!encoder_code.contains("panic"));
+}
+
+#[test]
+fn duplicate_variant_tags_are_rejected() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    write_project(
+        &root,
+        r#"
+// gloss!: decoder
+pub type Media {
+  // gloss!: tag = "image"
+  Photo(url: String)
+  Image(url: String)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let error = generate_for_project(&root, &registry).expect_err("duplicate tags");
+    match error {
+        GlossError::GenerationError(message) => {
+            assert!(message
+                .contains("Variants `Photo` and `Image` of type `Media` both use the tag \"image\""));
+        }
+        other => panic!("unexpected error kind: {other:?}"),
+    }
+}
+
+#[test]
+fn flag_words_inside_tag_values_are_not_flags() {
+    let temp = tempdir().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8 path");
+    let media_path = write_project(
+        &root,
+        r#"
+// gloss!: encoder(json), decoder
+pub type Media {
+  // gloss!: tag = "skip"
+  Skip(url: String)
+  // gloss!: tag = "deprecated"
+  Old(url: String)
+}
+"#,
+    );
+
+    let registry = BackendRegistry::new();
+    let generated = generate_for_project(&root, &registry).expect("generate project");
+    let groups = generated.get(&media_path).expect("media module generated");
+
+    let decoder_code = groups[0].get_decoder_code(true, false);
+    assert!(decoder_code.contains(r#""skip" -> {"#));
+    assert!(decoder_code.contains(r#""deprecated" -> {"#));
+    assert!(decoder_code.contains(r#""one of skip, deprecated")"#));
+
+    let encoder_code = groups[0].get_encoder_code(true, false);
+    assert!(encoder_code.contains(r#"#("type", json.string("skip"))"#));
+    assert!(!encoder_code.contains("is skipped by gloss"));
+}